@@ -41,6 +41,7 @@ impl Widget for HelpBox {
         match self.mode {
             Mode::View => render_markdown_help(self.expanded, area, buf),
             Mode::FileTree => render_file_tree_help(self.expanded, area, buf),
+            Mode::Tags => render_tags_help(self.expanded, area, buf),
         }
     }
 }
@@ -83,6 +84,10 @@ fn render_file_tree_help(expanded: bool, area: Rect, buf: &mut Buffer) {
             format!("/ or {}", KEY_CONFIG.search),
             "Search".to_string(),
         ]),
+        Row::new(vec![
+            format!("{}", KEY_CONFIG.tags),
+            "Browse tags".to_string(),
+        ]),
         Row::new(vec!["\u{21b5}", "Open file"]),
         Row::new(vec!["q", "Quit"]),
     ];
@@ -94,6 +99,44 @@ fn render_file_tree_help(expanded: bool, area: Rect, buf: &mut Buffer) {
     table.render(area, buf);
 }
 
+fn render_tags_help(expanded: bool, area: Rect, buf: &mut Buffer) {
+    if !expanded {
+        let text = Text::styled("? - Help", Style::default().fg(Color::LightGreen).bold());
+        text.render(area, buf);
+        return;
+    }
+
+    let header = Row::new(vec!["Key", "Action"]);
+
+    let key_actions = [
+        Row::new(vec![
+            format!("{} or \u{2193}", KEY_CONFIG.down),
+            "Move down".to_string(),
+        ]),
+        Row::new(vec![
+            format!("{} or \u{2191}", KEY_CONFIG.up),
+            "Move up".to_string(),
+        ]),
+        Row::new(vec![
+            format!("{}", KEY_CONFIG.top),
+            "Move to first tag".to_string(),
+        ]),
+        Row::new(vec![
+            format!("{}", KEY_CONFIG.bottom),
+            "Move to last tag".to_string(),
+        ]),
+        Row::new(vec!["\u{21b5}", "List files with tag"]),
+        Row::new(vec!["Esc", "Back to file tree"]),
+        Row::new(vec!["q", "Quit"]),
+    ];
+
+    let widths = [12, 20];
+
+    let table =
+        Table::new(key_actions, widths).header(header.fg(color_config().table_header_fg_color));
+    table.render(area, buf);
+}
+
 fn render_markdown_help(expandend: bool, area: Rect, buf: &mut Buffer) {
     if !expandend {
         let text = Text::styled("? - Help", Style::default().fg(Color::LightGreen).bold());
@@ -142,7 +185,7 @@ fn render_markdown_help(expandend: bool, area: Rect, buf: &mut Buffer) {
         ]),
         Row::new(vec![
             format!("{}", KEY_CONFIG.back),
-            "Go back to previous file".to_string(),
+            "Back (footnote or file)".to_string(),
         ]),
         Row::new(vec![
             format!("{}", KEY_CONFIG.file_tree),
@@ -152,11 +195,43 @@ fn render_markdown_help(expandend: bool, area: Rect, buf: &mut Buffer) {
             format!("{}", KEY_CONFIG.select_link),
             "Enter select mode".to_string(),
         ]),
+        Row::new(vec![
+            format!("{}", KEY_CONFIG.link_hints),
+            "Pick link by label".to_string(),
+        ]),
+        Row::new(vec![
+            format!("{}", KEY_CONFIG.select_table),
+            "Enter table mode".to_string(),
+        ]),
         Row::new(vec!["\u{21b5}", "Open link/file"]),
+        Row::new(vec![
+            format!("{}", KEY_CONFIG.yank),
+            "Copy link/code block".to_string(),
+        ]),
+        Row::new(vec![
+            format!("{}", KEY_CONFIG.yank_alt),
+            "Copy link to section".to_string(),
+        ]),
         Row::new(vec![
             format!("{}", KEY_CONFIG.edit),
             "Edit file".to_string(),
         ]),
+        Row::new(vec![
+            format!("{}", KEY_CONFIG.follow),
+            "Toggle follow mode".to_string(),
+        ]),
+        Row::new(vec![
+            format!("{}a", KEY_CONFIG.fold),
+            "Fold/open section".to_string(),
+        ]),
+        Row::new(vec![
+            format!("{0}M or {0}R", KEY_CONFIG.fold),
+            "Fold/open all sections".to_string(),
+        ]),
+        Row::new(vec![
+            format!("{}1 to {}6", KEY_CONFIG.fold, KEY_CONFIG.fold),
+            "Fold to heading level".to_string(),
+        ]),
         Row::new(vec!["q", "Quit"]),
     ];
 
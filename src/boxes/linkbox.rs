@@ -1,27 +1,41 @@
 use ratatui::{
     buffer::Buffer,
     layout::{Alignment, Rect},
+    text::Text,
     widgets::{Block, Borders, Paragraph, Widget, Wrap},
 };
 
 #[derive(Debug, Clone)]
 pub struct LinkBox {
-    message: String,
+    message: Text<'static>,
 }
 
 impl LinkBox {
     #[must_use]
     pub fn new(message: String) -> Self {
-        Self { message }
+        Self {
+            message: Text::from(message),
+        }
     }
 
     #[must_use]
     pub fn dimensions(&self) -> (u16, u16) {
-        ((self.message.len() / 30) as u16 + 4, 50)
+        let lines: usize = self
+            .message
+            .lines
+            .iter()
+            .map(|line| line.width() / 30 + 1)
+            .sum();
+        (lines as u16 + 3, 50)
     }
 
     pub fn set_message(&mut self, message: String) {
-        self.message = message;
+        self.message = Text::from(message);
+    }
+
+    /// Show styled text, like a footnote with its formatting
+    pub fn set_text(&mut self, text: Text<'static>) {
+        self.message = text;
     }
 }
 
@@ -7,6 +7,8 @@ use ratatui::{
 #[derive(Debug, Clone)]
 pub struct SearchBox {
     pub text: String,
+    /// Shown in front of the text, telling what the box is asking for
+    prompt: &'static str,
     pub cursor: usize,
     height: u16,
     width: u16,
@@ -19,6 +21,7 @@ impl SearchBox {
     pub fn new() -> Self {
         Self {
             text: String::new(),
+            prompt: "",
             cursor: 0,
             height: 2,
             width: 20,
@@ -74,6 +77,10 @@ impl SearchBox {
         self.y = y;
     }
 
+    pub fn set_prompt(&mut self, prompt: &'static str) {
+        self.prompt = prompt;
+    }
+
     pub fn set_width(&mut self, width: u16) {
         self.width = width;
     }
@@ -97,7 +104,7 @@ impl Default for SearchBox {
 
 impl Widget for SearchBox {
     fn render(self, area: Rect, buf: &mut Buffer) {
-        let paragraph = Paragraph::new(self.text)
+        let paragraph = Paragraph::new(format!("{}{}", self.prompt, self.text))
             .block(Block::default().borders(Borders::BOTTOM))
             .wrap(Wrap { trim: true });
         paragraph.render(area, buf);
@@ -1,16 +1,32 @@
-use std::{cmp, fs::read_to_string};
+use std::{
+    cmp,
+    fs::{self, read_to_string},
+};
 
 use crossterm::event::KeyCode;
 use notify::{PollWatcher, Watcher};
+use ratatui::{
+    style::Stylize,
+    text::{Line, Text},
+};
 
 use crate::{
-    nodes::{root::ComponentRoot, word::WordType},
-    pages::file_explorer::FileTree,
+    nodes::{
+        root::ComponentRoot,
+        table::{self, Delimiter},
+        word::WordType,
+    },
+    pages::{file_explorer::FileTree, markdown_renderer::footnote_line, tag_browser::TagBrowser},
     parser::parse_markdown,
     util::{
         App, Boxes, Jump, LinkType, Mode,
+        clipboard::copy_to_clipboard,
+        follow_scroll,
         general::GENERAL_CONFIG,
-        keys::{Action, key_to_action},
+        hints::{HintMatch, LinkHints},
+        is_allowed_scheme,
+        keys::{Action, KEY_CONFIG, key_to_action},
+        url_scheme, view_height,
     },
 };
 
@@ -28,12 +44,17 @@ pub fn handle_keyboard_input(
     height: u16,
     watcher: &mut PollWatcher,
 ) -> KeyBoardAction {
-    if key == KeyCode::Char('q') && app.boxes != Boxes::Search {
+    if key == KeyCode::Char('q') && !app.boxes.takes_input() {
         return KeyBoardAction::Exit;
     }
     match app.mode {
         Mode::FileTree => keyboard_mode_file_tree(key, app, markdown, file_tree, height, watcher),
-        Mode::View => keyboard_mode_view(key, app, markdown, height, watcher),
+        Mode::View => {
+            let action = keyboard_mode_view(key, app, markdown, file_tree, height, watcher);
+            app.update_follow(markdown.height(), height);
+            action
+        }
+        Mode::Tags => keyboard_mode_tags(key, app, file_tree, height),
     }
 }
 
@@ -130,11 +151,13 @@ pub fn keyboard_mode_file_tree(
                 app.mode = Mode::View;
                 app.help_box.set_mode(Mode::View);
                 app.select_index = 0;
+                app.report_parse_errors(markdown);
             }
             Action::Search => {
                 let file_height = file_tree.height(height);
                 app.search_box.set_position(10, file_height as u16 + 2);
                 app.search_box.set_width(20);
+                app.search_box.set_prompt("");
                 app.boxes = Boxes::Search;
                 app.help_box.close();
             }
@@ -156,6 +179,7 @@ pub fn keyboard_mode_file_tree(
                     app.reset();
                     app.mode = Mode::View;
                     app.help_box.set_mode(Mode::View);
+                    app.report_parse_errors(markdown);
                 }
                 Jump::FileTree => {
                     markdown.clear();
@@ -170,12 +194,19 @@ pub fn keyboard_mode_file_tree(
 
             Action::Escape => {
                 file_tree.unselect();
+                file_tree.filter_tag(None);
                 file_tree.search(None);
             }
 
             Action::Sort => {
                 file_tree.sort_name();
             }
+
+            Action::Tags => {
+                app.tag_browser = TagBrowser::new(file_tree.all_files());
+                app.mode = Mode::Tags;
+                app.help_box.set_mode(Mode::Tags);
+            }
             _ => {}
         },
         Boxes::LinkPreview => {
@@ -183,15 +214,59 @@ pub fn keyboard_mode_file_tree(
                 app.boxes = Boxes::None;
             }
         }
+        Boxes::TableFilter | Boxes::TableExport | Boxes::ConfirmOpen => {
+            app.boxes = Boxes::None;
+        }
     }
 
     KeyBoardAction::Continue
 }
 
+/// Keys on the tag browser page, see `Action::Tags`
+fn keyboard_mode_tags(
+    key: KeyCode,
+    app: &mut App,
+    file_tree: &mut FileTree,
+    height: u16,
+) -> KeyBoardAction {
+    match key_to_action(key) {
+        Action::Down => app.tag_browser.next(),
+        Action::Up => app.tag_browser.previous(),
+        Action::PageDown => app.tag_browser.next_page(height),
+        Action::PageUp => app.tag_browser.previous_page(height),
+        Action::ToTop => app.tag_browser.first(),
+        Action::ToBottom => app.tag_browser.last(),
+        Action::Enter => {
+            if let Some(tag) = app.tag_browser.selected() {
+                let tag = tag.name.clone();
+                show_tagged_files(app, file_tree, &tag);
+            }
+        }
+        Action::Escape | Action::Back | Action::ToFileTree | Action::Tags => {
+            app.mode = Mode::FileTree;
+            app.help_box.set_mode(Mode::FileTree);
+        }
+        Action::Help if GENERAL_CONFIG.help_menu => {
+            app.help_box.toggle();
+        }
+        _ => {}
+    }
+    KeyBoardAction::Continue
+}
+
+/// List the files with `tag` in the file tree
+fn show_tagged_files(app: &mut App, file_tree: &mut FileTree, tag: &str) {
+    file_tree.filter_tag(Some(tag));
+    file_tree.first();
+    app.mode = Mode::FileTree;
+    app.help_box.set_mode(Mode::FileTree);
+}
+
 fn keyboard_mode_view(
     key: KeyCode,
     app: &mut App,
     markdown: &mut ComponentRoot,
+    file_tree: &mut FileTree,
     height: u16,
     watcher: &mut PollWatcher,
 ) -> KeyBoardAction {
@@ -244,6 +319,66 @@ fn keyboard_mode_view(
             }
             _ => {}
         },
+        Boxes::TableFilter => {
+            match key {
+                KeyCode::Esc => {
+                    app.search_box.clear();
+                    app.boxes = Boxes::None;
+                }
+                KeyCode::Enter => {
+                    app.boxes = Boxes::None;
+                }
+                KeyCode::Char(c) => app.search_box.insert(c),
+                KeyCode::Backspace => app.search_box.delete(),
+                _ => return KeyBoardAction::Continue,
+            }
+            // Enter keeps the filter, Esc has already cleared it
+            let filter = app.search_box.content_str().to_owned();
+            if app.boxes == Boxes::None {
+                app.search_box.clear();
+            }
+            if let Some(table) = markdown.selected_table_mut() {
+                table.filter_table(&filter, app.width() - 2);
+            }
+            scroll_to_table_cursor(app, markdown, height);
+        }
+        Boxes::TableExport => match key {
+            KeyCode::Esc => {
+                app.search_box.clear();
+                app.boxes = Boxes::None;
+            }
+            KeyCode::Enter => {
+                let path = app.search_box.consume();
+                let rows = markdown
+                    .selected_table()
+                    .map(|table| table.table_rows())
+                    .unwrap_or_default();
+                let delimiter = Delimiter::from_path(&path);
+                let message = match fs::write(&path, table::to_delimited(&rows, delimiter)) {
+                    Ok(()) => format!("Saved the table as {} to {path}", delimiter.name()),
+                    Err(e) => format!("Could not save the table to {path}: {e}"),
+                };
+                app.message_box.set_message(message);
+                app.boxes = Boxes::Error;
+            }
+            KeyCode::Char(c) => {
+                app.search_box.insert(c);
+            }
+            KeyCode::Backspace => {
+                app.search_box.delete();
+            }
+            _ => {}
+        },
+        Boxes::None if app.link_hints.is_some() => {
+            return keyboard_link_hints(key, app, markdown, file_tree, height, watcher);
+        }
+        Boxes::None if app.fold_pending => {
+            app.fold_pending = false;
+            return keyboard_fold(key, app, markdown, height);
+        }
+        Boxes::None if app.table_selected => {
+            return keyboard_mode_table(key, app, markdown, height);
+        }
         Boxes::None => match key_to_action(key) {
             Action::Down => {
                 if app.selected {
@@ -329,17 +464,46 @@ fn keyboard_mode_view(
                     let prev_type = markdown.selected_underlying_type();
 
                     if prev_type == WordType::FootnoteInline {
+                        let number = link.trim_matches(['[', ']']);
+                        let text = match markdown.footnote(link) {
+                            Some(footnote) => Text::from(vec![
+                                Line::from(format!("Footnote {number}")).bold(),
+                                footnote_line(footnote),
+                            ]),
+                            None => Text::from(format!("Footnote {number} not found")),
+                        };
+                        app.link_box.set_text(text);
+                        app.boxes = Boxes::LinkPreview;
+                        return KeyBoardAction::Continue;
+                    }
+
+                    if prev_type == WordType::Abbreviation {
+                        show_abbreviation(app, markdown, link);
+                        return KeyBoardAction::Continue;
+                    }
+
+                    if prev_type == WordType::Tag {
+                        let tag = link.trim_start_matches('#').to_lowercase();
+                        let count = file_tree
+                            .all_files()
+                            .iter()
+                            .filter(|file| file.tags.contains(&tag))
+                            .count();
+                        let files = if count == 1 { "file" } else { "files" };
                         app.link_box
-                            .set_message(format!("Footnote: {}", markdown.find_footnote(link)));
+                            .set_message(format!("Tag: {link}\nIn {count} {files}"));
                         app.boxes = Boxes::LinkPreview;
                         return KeyBoardAction::Continue;
                     }
 
-                    let message = match LinkType::from(link) {
+                    let mut message = match LinkType::from(link) {
                         LinkType::Internal(e) => format!("Internal link: {e}"),
                         LinkType::External(e) => format!("External link: {e}"),
                         LinkType::MarkdownFile(e) => format!("Markdown file: {e}"),
                     };
+                    if let Some(title) = markdown.link_title(link) {
+                        message = format!("{title}\n{message}");
+                    }
 
                     app.link_box.set_message(message);
                     app.boxes = Boxes::LinkPreview;
@@ -407,6 +571,29 @@ fn keyboard_mode_view(
                 };
             }
 
+            // Enter table mode on the first table at or below the current
+            // scroll position. Mutually exclusive with the other selections.
+            Action::SelectTable => {
+                let tables = markdown.table_index_and_height();
+                if tables.is_empty() {
+                    app.message_box.set_message("No tables found".to_string());
+                    app.boxes = Boxes::Error;
+                    return KeyBoardAction::Continue;
+                }
+
+                app.selected = false;
+                markdown.deselect();
+                app.details_selected = false;
+                markdown.deselect_details();
+
+                let index = tables
+                    .iter()
+                    .find(|(_, y)| *y >= app.vertical_scroll)
+                    .or(tables.last())
+                    .map_or(0, |(i, _)| *i);
+                select_table(app, markdown, index, height);
+            }
+
             // Cycle to the details summary nearest (and at-or-below) the
             // current scroll position. Mirrors `SelectLink` but for
             // `<details>` blocks. Mutually exclusive with link selection.
@@ -450,6 +637,7 @@ fn keyboard_mode_view(
                 app.search_box.clear();
                 app.search_box.set_position(2, height - 3);
                 app.search_box.set_width(GENERAL_CONFIG.width - 3);
+                app.search_box.set_prompt("");
                 app.boxes = Boxes::Search;
                 app.help_box.close();
             }
@@ -496,6 +684,77 @@ fn keyboard_mode_view(
 
             Action::Edit => return KeyBoardAction::Edit,
 
+            Action::Follow => {
+                app.follow = !app.follow;
+                app.follow_paused = false;
+                if app.follow {
+                    app.vertical_scroll = follow_scroll(markdown.height(), height);
+                }
+            }
+
+            Action::Fold => {
+                app.fold_pending = true;
+            }
+
+            // Copy the selected link, else the code block at the top of the
+            // screen as it is written
+            Action::Yank => {
+                if app.selected {
+                    let link = markdown.selected().to_owned();
+                    yank(app, &link, &link);
+                } else if let Some(code) =
+                    markdown.code_block_at(app.vertical_scroll, view_height(height))
+                {
+                    let code = String::from_utf8_lossy(&code.content_as_bytes())
+                        .trim_matches('\n')
+                        .to_owned();
+                    let lines = code.lines().count();
+                    let what = if lines == 1 { "line" } else { "lines" };
+                    yank(app, &code, &format!("the code block, {lines} {what}"));
+                } else {
+                    app.message_box
+                        .set_message("No link selected or code block on screen".to_string());
+                    app.boxes = Boxes::Error;
+                }
+            }
+
+            // Copy a link to the section at the top of the screen
+            Action::YankAlt => {
+                let file = markdown
+                    .file_name()
+                    .map(|file| file.trim_start_matches("./"))
+                    .unwrap_or_default();
+                let link = match markdown.section_anchor(app.vertical_scroll) {
+                    Some(anchor) => format!("{file}#{anchor}"),
+                    None => file.to_owned(),
+                };
+                if link.is_empty() {
+                    app.message_box
+                        .set_message("No file or heading to link to".to_string());
+                    app.boxes = Boxes::Error;
+                } else {
+                    yank(app, &link, &link);
+                }
+            }
+
+            // Label the links on screen, the next keys pick one
+            Action::LinkHints => {
+                match LinkHints::new(markdown, app.vertical_scroll, view_height(height)) {
+                    Some(hints) => {
+                        app.selected = false;
+                        markdown.deselect();
+                        app.details_selected = false;
+                        markdown.deselect_details();
+                        app.link_hints = Some(hints);
+                    }
+                    None => {
+                        app.message_box
+                            .set_message("No links on screen".to_string());
+                        app.boxes = Boxes::Error;
+                    }
+                }
+            }
+
             Action::Escape => {
                 app.selected = false;
                 markdown.deselect();
@@ -521,16 +780,43 @@ fn keyboard_mode_view(
                 let prev_type = markdown.selected_underlying_type();
 
                 if prev_type == WordType::FootnoteInline {
-                    app.message_box.set_message(markdown.find_footnote(link));
-                    app.boxes = Boxes::Error;
+                    match markdown.footnote_offset(link) {
+                        Ok(offset) => {
+                            app.footnote_returns.push(app.vertical_scroll);
+                            app.vertical_scroll =
+                                cmp::min(offset, markdown.height().saturating_sub(height / 2));
+                        }
+                        Err(message) => {
+                            app.message_box.set_message(message);
+                            app.boxes = Boxes::Error;
+                        }
+                    }
                     markdown.deselect();
                     app.selected = false;
                     return KeyBoardAction::Continue;
                 }
 
+                // An abbreviation has nowhere to go, show what it stands for
+                if prev_type == WordType::Abbreviation {
+                    show_abbreviation(app, markdown, link);
+                    return KeyBoardAction::Continue;
+                }
+
+                // A tag lists the files with it, `b` comes back here
+                if prev_type == WordType::Tag {
+                    let tag = link.trim_start_matches('#').to_owned();
+                    if let Some(file) = markdown.file_name() {
+                        app.history.push(Jump::File(file.to_string()));
+                    }
+                    app.reset();
+                    show_tagged_files(app, file_tree, &tag);
+                    return KeyBoardAction::Continue;
+                }
+
                 match LinkType::from(link) {
                     LinkType::Internal(heading) => {
-                        app.vertical_scroll = if let Ok(index) = markdown.heading_offset(heading) {
+                        let heading = heading.to_owned();
+                        app.vertical_scroll = if let Ok(index) = markdown.heading_offset(&heading) {
                             cmp::min(index, markdown.height().saturating_sub(height / 2))
                         } else {
                             app.message_box
@@ -541,7 +827,8 @@ fn keyboard_mode_view(
                         };
                     }
                     LinkType::External(url) => {
-                        let _ = open::that(url);
+                        let url = url.to_owned();
+                        open_external(app, &url);
                     }
                     LinkType::MarkdownFile(url) => {
                         // Remove the first character, which is a '/'
@@ -552,7 +839,7 @@ fn keyboard_mode_view(
                         };
 
                         let (url, heading) = if let Some((url, heading)) = url.split_once('#') {
-                            (url.to_string(), Some(heading.to_string().to_lowercase()))
+                            (url.to_string(), Some(heading.to_string()))
                         } else {
                             (url.to_string(), None)
                         };
@@ -595,12 +882,20 @@ fn keyboard_mode_view(
 
                         app.reset();
                         app.vertical_scroll = index;
+                        app.report_parse_errors(markdown);
                     }
                 }
                 markdown.deselect();
                 app.selected = false;
             }
 
+            // Back from a footnote to where it was referenced
+            Action::Back if !app.footnote_returns.is_empty() => {
+                if let Some(scroll) = app.footnote_returns.pop() {
+                    app.vertical_scroll = scroll;
+                }
+            }
+
             Action::Back => match app.history.pop() {
                 Jump::File(e) => {
                     let text = if let Ok(file) = read_to_string(&e) {
@@ -618,6 +913,7 @@ fn keyboard_mode_view(
                     app.reset();
                     app.mode = Mode::View;
                     app.help_box.set_mode(Mode::View);
+                    app.report_parse_errors(markdown);
                 }
                 Jump::FileTree => {
                     markdown.clear();
@@ -636,6 +932,248 @@ fn keyboard_mode_view(
                 app.boxes = Boxes::None;
             }
         }
+        Boxes::ConfirmOpen => match key {
+            KeyCode::Enter if is_allowed_scheme(&app.open_url) => {
+                app.boxes = Boxes::None;
+                if let Err(e) = open::that(&app.open_url) {
+                    app.message_box
+                        .set_message(format!("Could not open {}: {e}", app.open_url));
+                    app.boxes = Boxes::Error;
+                }
+            }
+            KeyCode::Char(c) if c == KEY_CONFIG.yank => {
+                let url = std::mem::take(&mut app.open_url);
+                yank(app, &url, &url);
+            }
+            KeyCode::Esc => app.boxes = Boxes::None,
+            _ => {}
+        },
     }
     KeyBoardAction::Continue
 }
+
+/// Ask before opening an external link, showing all of it. Links with a
+/// scheme not in `link_schemes` can only be copied, and with `--no-open` or
+/// `copy_links` they are copied right away.
+fn open_external(app: &mut App, url: &str) {
+    if app.no_open || GENERAL_CONFIG.copy_links {
+        yank(app, url, url);
+        return;
+    }
+    app.open_url = url.to_owned();
+    let text = if is_allowed_scheme(url) {
+        vec![
+            Line::from("Open this link?").bold(),
+            Line::from(url.to_owned()),
+            Line::from(format!(
+                "\u{21b5} open, {} copy, Esc cancel",
+                KEY_CONFIG.yank
+            )),
+        ]
+    } else {
+        let scheme = url_scheme(url).unwrap_or("file");
+        vec![
+            Line::from(format!("Not opening {scheme} links, see link_schemes")).bold(),
+            Line::from(url.to_owned()),
+            Line::from(format!("{} copy, Esc cancel", KEY_CONFIG.yank)),
+        ]
+    };
+    app.link_box.set_text(Text::from(text));
+    app.boxes = Boxes::ConfirmOpen;
+}
+
+/// Show what an abbreviation stands for, on hover and on `Enter` alike
+fn show_abbreviation(app: &mut App, markdown: &ComponentRoot, abbreviation: &str) {
+    let title = markdown.abbreviation(abbreviation).unwrap_or_default();
+    app.link_box.set_message(format!("{abbreviation}: {title}"));
+    app.boxes = Boxes::LinkPreview;
+}
+
+/// Copy `text` to the clipboard with OSC 52 and tell what was copied
+fn yank(app: &mut App, text: &str, what: &str) {
+    let message = match copy_to_clipboard(text) {
+        Ok(()) => format!("Copied {what}"),
+        Err(e) => format!("Could not copy {what}: {e}"),
+    };
+    app.message_box.set_message(message);
+    app.boxes = Boxes::Error;
+}
+
+/// Keys while the links on screen have labels, see `Action::LinkHints`.
+/// Typing a label follows its link like `Enter`, typing it in uppercase
+/// hovers it like `Action::Hover`.
+fn keyboard_link_hints(
+    key: KeyCode,
+    app: &mut App,
+    markdown: &mut ComponentRoot,
+    file_tree: &mut FileTree,
+    height: u16,
+    watcher: &mut PollWatcher,
+) -> KeyBoardAction {
+    let Some(hints) = app.link_hints.as_mut() else {
+        return KeyBoardAction::Continue;
+    };
+    match key {
+        KeyCode::Esc => app.link_hints = None,
+        KeyCode::Backspace => hints.pop(),
+        KeyCode::Char(c) => {
+            if let HintMatch::Found(index) = hints.push(c) {
+                app.link_hints = None;
+                if markdown.select(index).is_err() {
+                    return KeyBoardAction::Continue;
+                }
+                app.select_index = index;
+                app.selected = true;
+                let key = if c.is_ascii_uppercase() {
+                    KeyCode::Char(KEY_CONFIG.hover)
+                } else {
+                    KeyCode::Enter
+                };
+                return keyboard_mode_view(key, app, markdown, file_tree, height, watcher);
+            }
+        }
+        _ => {}
+    }
+    KeyBoardAction::Continue
+}
+
+/// The key after `Action::Fold`, like vim's `za`, `zM`, `zR` and `z1` to
+/// `z6`. `a` folds or opens the section at the top of the screen, `M` folds
+/// all sections and details, `R` opens them and a digit folds the sections
+/// under headings of that level and deeper.
+fn keyboard_fold(
+    key: KeyCode,
+    app: &mut App,
+    markdown: &mut ComponentRoot,
+    height: u16,
+) -> KeyBoardAction {
+    let scroll = match key {
+        KeyCode::Char('a') => match markdown.toggle_section_at(app.vertical_scroll) {
+            Ok(scroll) => scroll,
+            Err(message) => {
+                app.message_box.set_message(message);
+                app.boxes = Boxes::Error;
+                return KeyBoardAction::Continue;
+            }
+        },
+        KeyCode::Char('M') => markdown.fold_all(true, app.vertical_scroll),
+        KeyCode::Char('R') => markdown.fold_all(false, app.vertical_scroll),
+        KeyCode::Char(c @ '1'..='6') => {
+            let level = c.to_digit(10).map(|level| level as u8);
+            markdown.fold_headings(level, app.vertical_scroll)
+        }
+        _ => return KeyBoardAction::Continue,
+    };
+
+    // Links and details further down moved or were hidden
+    app.selected = false;
+    markdown.deselect();
+    app.details_selected = false;
+    markdown.deselect_details();
+    app.vertical_scroll = cmp::min(scroll, markdown.height().saturating_sub(height / 2));
+    markdown.set_scroll(app.vertical_scroll);
+    KeyBoardAction::Continue
+}
+
+/// Keys while a table is in table mode, see `Action::SelectTable`
+fn keyboard_mode_table(
+    key: KeyCode,
+    app: &mut App,
+    markdown: &mut ComponentRoot,
+    height: u16,
+) -> KeyBoardAction {
+    let width = app.width() - 2;
+    let Some(table) = markdown.selected_table_mut() else {
+        // The document was reloaded, leaving table mode behind
+        app.table_selected = false;
+        return KeyBoardAction::Continue;
+    };
+
+    match (key, key_to_action(key)) {
+        (KeyCode::Left, _) | (_, Action::HalfPageUp) => table.move_table_cursor(0, -1),
+        (KeyCode::Right, _) | (_, Action::HalfPageDown) => table.move_table_cursor(0, 1),
+        (_, Action::Down) => table.move_table_cursor(1, 0),
+        (_, Action::Up) => table.move_table_cursor(-1, 0),
+        (_, Action::ToTop) => table.move_table_cursor(isize::MIN, 0),
+        (_, Action::ToBottom) => table.move_table_cursor(isize::MAX, 0),
+        (_, Action::Sort) => table.sort_table(width),
+        (_, Action::Search) => {
+            app.search_box.clear();
+            if let Some(view) = table.table_view() {
+                view.filter.chars().for_each(|c| app.search_box.insert(c));
+            }
+            app.search_box.set_prompt("Filter: ");
+            app.search_box.set_position(2, height - 3);
+            app.search_box.set_width(GENERAL_CONFIG.width - 3);
+            app.boxes = Boxes::TableFilter;
+            app.help_box.close();
+        }
+        (_, action @ (Action::Yank | Action::YankAlt)) => {
+            let delimiter = match action {
+                Action::YankAlt => Delimiter::Tab,
+                _ => Delimiter::Comma,
+            };
+            let text = table::to_delimited(&table.table_rows(), delimiter);
+            yank(app, &text, &format!("the table as {}", delimiter.name()));
+        }
+        (_, Action::Export) => {
+            app.search_box.clear();
+            "table.csv".chars().for_each(|c| app.search_box.insert(c));
+            app.search_box.set_prompt("Save as: ");
+            app.search_box.set_position(2, height - 3);
+            app.search_box.set_width(GENERAL_CONFIG.width - 3);
+            app.boxes = Boxes::TableExport;
+            app.help_box.close();
+        }
+        (_, Action::SelectTable) => {
+            let last = markdown.table_index_and_height().len().saturating_sub(1);
+            let index = cmp::min(app.table_select_index + 1, last);
+            select_table(app, markdown, index, height);
+            return KeyBoardAction::Continue;
+        }
+        (_, Action::Escape) => {
+            markdown.deselect_table(width);
+            markdown.set_scroll(app.vertical_scroll);
+            app.table_selected = false;
+            return KeyBoardAction::Continue;
+        }
+        (_, Action::Help) if GENERAL_CONFIG.help_menu => {
+            app.help_box.toggle();
+        }
+        _ => {}
+    }
+
+    scroll_to_table_cursor(app, markdown, height);
+    KeyBoardAction::Continue
+}
+
+fn select_table(app: &mut App, markdown: &mut ComponentRoot, index: usize, height: u16) {
+    match markdown.select_table(index, app.width() - 2) {
+        Ok(_) => {
+            app.table_select_index = index;
+            app.table_selected = true;
+            scroll_to_table_cursor(app, markdown, height);
+        }
+        Err(_) => {
+            app.table_selected = false;
+        }
+    }
+}
+
+/// Scroll just enough to show the row of the cursor. Sorting and filtering
+/// change the height of the table, so the offsets are updated first.
+fn scroll_to_table_cursor(app: &mut App, markdown: &mut ComponentRoot, height: u16) {
+    markdown.set_scroll(app.vertical_scroll);
+    let Some(table) = markdown.selected_table() else {
+        return;
+    };
+    let Some(row) = table.table_cursor_height() else {
+        return;
+    };
+    let cursor = table.y_offset() + row;
+    let visible = height.saturating_sub(6);
+    if cursor < app.vertical_scroll || cursor >= app.vertical_scroll + visible {
+        app.vertical_scroll = cursor.saturating_sub(height / 3);
+        markdown.set_scroll(app.vertical_scroll);
+    }
+}
@@ -12,9 +12,13 @@ use std::{
 use md_tui::event_handler::{KeyBoardAction, handle_keyboard_input};
 use md_tui::nodes::root::{Component, ComponentRoot};
 use md_tui::pages::file_explorer::{FileTree, MdFile};
-use md_tui::parser::parse_markdown;
+use md_tui::pages::markdown_renderer::{find_rendered, render_hyperlinks};
+use md_tui::parser::{self, ParserBackend, parse_markdown};
 use md_tui::search::find_md_files_channel;
-use md_tui::util::{self, App, Boxes, Mode, destruct_terminal, general::GENERAL_CONFIG};
+use md_tui::util::{
+    self, App, Boxes, Mode, colors::color_config, destruct_terminal, general::GENERAL_CONFIG,
+    hints::LinkHints, piped::PipedInput, view_height,
+};
 
 use crossterm::{
     cursor,
@@ -26,9 +30,10 @@ use crossterm::{
 use notify::{Config, PollWatcher, Watcher};
 use ratatui::{
     DefaultTerminal, Frame,
+    buffer::{Buffer, CellDiffOption},
     layout::Rect,
-    style::{Color, Stylize},
-    widgets::{Block, Clear},
+    style::{Color, Modifier, Style, Stylize},
+    widgets::{Block, Clear, Widget},
 };
 use ratatui_image::{FilterType, Resize, StatefulImage};
 
@@ -42,21 +47,48 @@ fn main() -> Result<(), Box<dyn Error>> {
         better_panic::Settings::auto().create_panic_handler()(panic_info);
     }));
 
-    let args: Vec<String> = std::env::args().collect();
-    if args
-        .get(1)
-        .is_some_and(|a| a == "--version" || a == "-V" || a == "-v")
-    {
+    let args = Args::parse();
+    if args.version {
         println!("mdt {}", env!("CARGO_PKG_VERSION"));
         std::process::exit(0);
     }
 
+    // `--parser` wins over the config
+    let chosen = match &args.parser {
+        Some(name) => Some((name, "--parser")),
+        None => GENERAL_CONFIG
+            .parser
+            .as_ref()
+            .map(|name| (name, "the config")),
+    };
+    if let Some((name, source)) = chosen {
+        match name.parse::<ParserBackend>() {
+            Ok(backend) if backend.is_available() => parser::set_parser_backend(backend),
+            Ok(_) => {
+                eprintln!(
+                    "mdt: parser `{name}` from {source} requires building with the `commonmark` feature"
+                );
+                std::process::exit(2);
+            }
+            Err(e) => {
+                eprintln!("mdt: {e}, from {source}");
+                std::process::exit(2);
+            }
+        }
+    }
+
+    if args.diagnose {
+        std::process::exit(diagnose(args.file.as_deref()));
+    }
+
     let mut terminal = ratatui::init();
 
     // create app and run it
     let tick_rate = Duration::from_millis(100);
-    let app = App::default();
-    let res = run_app(&mut terminal, app, tick_rate);
+    let mut app = App::default();
+    app.follow = args.follow;
+    app.no_open = args.no_open;
+    let res = run_app(&mut terminal, app, tick_rate, args.file);
 
     // restore terminal
     ratatui::restore();
@@ -68,7 +100,130 @@ fn main() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
-fn run_app(terminal: &mut DefaultTerminal, mut app: App, tick_rate: Duration) -> io::Result<()> {
+/// Command line arguments. The first argument that is not a flag is the file to
+/// open.
+#[derive(Default)]
+struct Args {
+    file: Option<String>,
+    version: bool,
+    follow: bool,
+    no_open: bool,
+    diagnose: bool,
+    parser: Option<String>,
+}
+
+impl Args {
+    fn parse() -> Self {
+        let mut args = Self::default();
+        let mut iter = std::env::args().skip(1);
+        while let Some(arg) = iter.next() {
+            match arg.as_str() {
+                "--version" | "-V" | "-v" => args.version = true,
+                "--follow" | "-f" => args.follow = true,
+                "--no-open" => args.no_open = true,
+                "--diagnose" => args.diagnose = true,
+                "--parser" => {
+                    let Some(name) = iter.next() else {
+                        eprintln!("mdt: --parser needs a value, `pest` or `commonmark`");
+                        std::process::exit(2);
+                    };
+                    args.parser = Some(name);
+                }
+                _ if arg.starts_with("--parser=") => {
+                    args.parser = Some(arg["--parser=".len()..].to_string());
+                }
+                _ if args.file.is_none() => args.file = Some(arg),
+                _ => {}
+            }
+        }
+        args
+    }
+}
+
+/// Print the errors of the pest grammar for a file, or for stdin when no file
+/// is given, whichever parser is chosen. Returns the process exit code.
+fn diagnose(file: Option<&str>) -> i32 {
+    let name = file.unwrap_or("<stdin>");
+    let content = match file {
+        Some(file) => read_to_string(file),
+        None => io::read_to_string(io::stdin()),
+    };
+    let content = match content {
+        Ok(content) => content,
+        Err(err) => {
+            eprintln!("{name}: {err}");
+            return 2;
+        }
+    };
+
+    if parser::parser_backend() != ParserBackend::Pest {
+        eprintln!(
+            "mdt: --diagnose checks the pest grammar, the commonmark parser accepts any input"
+        );
+    }
+    let errors = parser::diagnose(&content);
+    for error in &errors {
+        println!("{name}:{error}");
+    }
+    if errors.is_empty() {
+        println!("{name}: no grammar errors");
+        0
+    } else {
+        1
+    }
+}
+
+/// Read piped stdin on a background thread. Every chunk is forwarded as soon
+/// as it arrives, so output from a producer that is still running can be
+/// rendered before it finishes.
+fn spawn_stdin_reader() -> mpsc::Receiver<String> {
+    let (tx, rx) = mpsc::channel();
+
+    thread::spawn(move || {
+        let mut stdin = io::stdin().lock();
+        let mut buf = [0; 4096];
+        let mut pending = Vec::new();
+
+        loop {
+            let n = match stdin.read(&mut buf) {
+                Ok(0) => break,
+                Ok(n) => n,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(_) => break,
+            };
+            pending.extend_from_slice(&buf[..n]);
+
+            // Hold back a multi-byte character split across two reads
+            let valid = match std::str::from_utf8(&pending) {
+                Ok(s) => s.len(),
+                Err(e) if e.error_len().is_none() => e.valid_up_to(),
+                Err(_) => pending.len(),
+            };
+            if valid == 0 {
+                continue;
+            }
+
+            let chunk = String::from_utf8_lossy(&pending[..valid]).into_owned();
+            pending.drain(..valid);
+            if tx.send(chunk).is_err() {
+                return;
+            }
+        }
+
+        if !pending.is_empty() {
+            let _ = tx.send(String::from_utf8_lossy(&pending).into_owned());
+        }
+    });
+
+    rx
+}
+
+fn run_app(
+    terminal: &mut DefaultTerminal,
+    mut app: App,
+    tick_rate: Duration,
+    file: Option<String>,
+) -> io::Result<()> {
     let (f_tx, f_rx) = mpsc::channel::<Option<MdFile>>();
 
     thread::spawn(move || find_md_files_channel(f_tx.clone()));
@@ -86,24 +241,22 @@ fn run_app(terminal: &mut DefaultTerminal, mut app: App, tick_rate: Duration) ->
     app.set_width(terminal.size()?.width - 1);
     let mut markdown = parse_markdown(None, EMPTY_FILE, app.width() - 2);
 
-    let potential_input = io::stdin();
-    let mut stdin_buf = String::new();
+    let mut piped = None;
 
-    let args: Vec<String> = std::env::args().collect();
-    if let Some(arg) = args.get(1) {
+    if let Some(arg) = file.as_deref() {
         if let Ok(file) = read_to_string(arg) {
             let path = std::path::Path::new(arg);
             let _ = watcher.watch(path, notify::RecursiveMode::NonRecursive);
             markdown = parse_markdown(Some(arg), &file, app.width() - 2);
             app.mode = Mode::View;
+            app.report_parse_errors(&markdown);
         } else {
             app.message_box
                 .set_message(format!("Could not open file {arg}"));
             app.boxes = Boxes::Error;
         }
-    } else if !potential_input.is_terminal() {
-        let _ = potential_input.lock().read_to_string(&mut stdin_buf);
-        markdown = parse_markdown(None, &stdin_buf, app.width() - 2);
+    } else if !io::stdin().is_terminal() {
+        piped = Some(PipedInput::new(spawn_stdin_reader()));
         app.mode = Mode::View;
     }
 
@@ -112,6 +265,19 @@ fn run_app(terminal: &mut DefaultTerminal, mut app: App, tick_rate: Duration) ->
     loop {
         let height = terminal.size()?.height;
 
+        // Only reparse piped input while still viewing it, the user may have
+        // moved on to the file tree or another file.
+        if let Some(piped) = &mut piped
+            && let Some(text) = piped.receive(Instant::now())
+            && markdown.file_name().is_none()
+            && app.mode == Mode::View
+        {
+            let started = Instant::now();
+            markdown = reparse(&markdown, text, app.width() - 2);
+            piped.parsed(Instant::now(), started.elapsed());
+            app.keep_in_view(markdown.height(), height);
+        }
+
         for event in rx.try_iter() {
             if event.is_err() {
                 continue;
@@ -120,19 +286,24 @@ fn run_app(terminal: &mut DefaultTerminal, mut app: App, tick_rate: Duration) ->
 
             if let notify::EventKind::Modify(_) = event.kind {
                 if let Ok(file) = read_to_string(markdown.file_name().unwrap()) {
-                    markdown =
-                        parse_markdown(Some(markdown.file_name().unwrap()), &file, app.width() - 2);
+                    markdown = reparse(&markdown, &file, app.width() - 2);
                     app.mode = Mode::View;
-                    app.vertical_scroll = cmp::min(
-                        app.vertical_scroll,
-                        markdown.height().saturating_sub(height / 2),
-                    );
+                    app.keep_in_view(markdown.height(), height);
+                    app.report_parse_errors(&markdown);
                 }
 
                 break;
             }
         }
         if app.set_width(terminal.size()?.width - 1) {
+            if let Some(piped) = &piped
+                && markdown.file_name().is_none()
+                && app.mode == Mode::View
+            {
+                markdown = reparse(&markdown, piped.text(), app.width() - 2);
+                app.keep_in_view(markdown.height(), height);
+                continue;
+            }
             let url = if let Some(url) = markdown.file_name() {
                 url
             } else {
@@ -149,7 +320,8 @@ fn run_app(terminal: &mut DefaultTerminal, mut app: App, tick_rate: Duration) ->
                 app.mode = Mode::FileTree;
                 continue;
             };
-            markdown = parse_markdown(markdown.file_name(), &text, app.width() - 2);
+            markdown = reparse(&markdown, &text, app.width() - 2);
+            app.keep_in_view(markdown.height(), height);
         }
 
         markdown.set_scroll(app.vertical_scroll);
@@ -173,10 +345,13 @@ fn run_app(terminal: &mut DefaultTerminal, mut app: App, tick_rate: Duration) ->
                             }
                         }
                     }
-                    render_file_tree(f, &app, file_tree.clone());
+                    render_page(f, &app, file_tree.clone());
+                }
+                Mode::Tags => {
+                    render_page(f, &app, app.tag_browser.clone());
                 }
             }
-            if app.boxes == Boxes::Search {
+            if app.boxes.takes_input() {
                 let (search_height, search_width) = app.search_box.dimensions();
                 let search_area = Rect {
                     x: app.search_box.x(),
@@ -198,7 +373,7 @@ fn run_app(terminal: &mut DefaultTerminal, mut app: App, tick_rate: Duration) ->
                     f.render_widget(Clear, error_area);
                     f.render_widget(app.message_box.clone(), error_area);
                 }
-            } else if app.boxes == Boxes::LinkPreview {
+            } else if matches!(app.boxes, Boxes::LinkPreview | Boxes::ConfirmOpen) {
                 let (link_height, link_width) = app.link_box.dimensions();
                 let link_area = Rect {
                     x: height / 2,
@@ -247,7 +422,17 @@ fn run_app(terminal: &mut DefaultTerminal, mut app: App, tick_rate: Duration) ->
     }
 }
 
-fn render_file_tree(f: &mut Frame, app: &App, file_tree: FileTree) {
+/// Parse the document being viewed again, after it changed or for a new
+/// width, keeping what is folded
+fn reparse(markdown: &ComponentRoot, text: &str, width: u16) -> ComponentRoot {
+    let folds = markdown.fold_state();
+    let mut reparsed = parse_markdown(markdown.file_name(), text, width);
+    reparsed.restore_fold_state(&folds);
+    reparsed
+}
+
+/// Render the file tree or the tag browser
+fn render_page(f: &mut Frame, app: &App, page: impl Widget) {
     let size = f.area();
     let x = match GENERAL_CONFIG.centering {
         util::general::Centering::Left => 2,
@@ -263,7 +448,7 @@ fn render_file_tree(f: &mut Frame, app: &App, file_tree: FileTree) {
         width: app.width() - 3,
         ..size
     };
-    f.render_widget(file_tree, area);
+    f.render_widget(page, area);
 
     if GENERAL_CONFIG.help_menu {
         let area = Rect {
@@ -295,11 +480,7 @@ fn render_markdown(f: &mut Frame, app: &App, markdown: &mut ComponentRoot) {
 
     let area = Rect {
         width: cmp::min(app.width() - 3, size.width - 1),
-        height: if GENERAL_CONFIG.help_menu {
-            size.height.saturating_sub(5)
-        } else {
-            size.height
-        },
+        height: view_height(size.height),
         x,
         ..size
     };
@@ -319,6 +500,9 @@ fn render_markdown(f: &mut Frame, app: &App, markdown: &mut ComponentRoot) {
                 f.render_widget(comp.clone(), area);
             }
             Component::Image(img) => {
+                if img.is_hidden() {
+                    continue;
+                }
                 if img.y_offset().saturating_sub(img.scroll_offset()) >= area.height
                     || (img.y_offset() + img.height()).saturating_sub(img.scroll_offset()) == 0
                 {
@@ -353,12 +537,21 @@ fn render_markdown(f: &mut Frame, app: &App, markdown: &mut ComponentRoot) {
         }
     }
 
+    // A clicked hyperlink is opened without asking
+    if GENERAL_CONFIG.hyperlinks && !app.no_open {
+        render_hyperlinks(f.buffer_mut(), area, markdown, app.vertical_scroll);
+    }
+
+    if let Some(hints) = &app.link_hints {
+        render_link_hints(f.buffer_mut(), hints, area, app.vertical_scroll);
+    }
+
     // Render a block at the bottom to show the current mode
     let block = Block::default().bg(Color::Black);
     let area = if app.help_box.expanded() {
         Rect {
-            y: size.height.saturating_sub(19),
-            height: cmp::min(18, size.height),
+            y: size.height.saturating_sub(27),
+            height: cmp::min(26, size.height),
             x,
             width: area.width - 1,
         }
@@ -379,8 +572,8 @@ fn render_markdown(f: &mut Frame, app: &App, markdown: &mut ComponentRoot) {
     let area = if app.help_box.expanded() {
         Rect {
             x: x + 2,
-            y: size.height.saturating_sub(18),
-            height: cmp::min(16, size.height),
+            y: size.height.saturating_sub(26),
+            height: cmp::min(24, size.height),
             width: app.width() - 5,
         }
     } else {
@@ -392,11 +585,38 @@ fn render_markdown(f: &mut Frame, app: &App, markdown: &mut ComponentRoot) {
         }
     };
 
-    if app.boxes != Boxes::Search && GENERAL_CONFIG.help_menu {
+    if !app.boxes.takes_input() && GENERAL_CONFIG.help_menu {
         f.render_widget(app.help_box, area);
     }
 }
 
+/// Put the labels of the link hints over the start of their links. Where a
+/// word ends up is up to the renderer, so the links are looked up in the
+/// rendered lines, in document order.
+fn render_link_hints(buf: &mut Buffer, hints: &LinkHints, area: Rect, scroll: u16) {
+    let style = Style::default()
+        .fg(color_config().link_hint_fg_color)
+        .bg(color_config().link_hint_bg_color)
+        .add_modifier(Modifier::BOLD);
+    let typed = hints.input().len();
+    let mut from = (area.y, area.x);
+    for hint in hints.hints() {
+        let y = area.y + hint.y.saturating_sub(scroll);
+        let start = cmp::max(from, (y, area.x));
+        let (y, x) = find_rendered(buf, area, &hint.text, start).unwrap_or((y, area.x));
+        from = (y, x + 1);
+        for (i, c) in hint.label.chars().skip(typed).enumerate() {
+            let x = x + i as u16;
+            if x < area.right() && y < area.bottom() {
+                buf[(x, y)]
+                    .set_char(c)
+                    .set_style(style)
+                    .set_diff_option(CellDiffOption::None);
+            }
+        }
+    }
+}
+
 fn open_editor(f: &mut Frame, app: &mut App, file_name: Option<&str>) {
     let editor = if let Ok(editor) = env::var("EDITOR") {
         editor
@@ -0,0 +1,120 @@
+use ratatui::style::Color;
+
+use crate::util::{colors::color_config, general::GENERAL_CONFIG};
+
+/// The kind of a GitHub alert or Obsidian callout, `> [!tip]`. Obsidian's
+/// aliases map to the kind they stand for, and kinds nobody knows about are
+/// shown as notes, like Obsidian does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Callout {
+    Note,
+    Abstract,
+    Info,
+    Todo,
+    Tip,
+    Important,
+    Success,
+    Question,
+    Warning,
+    Caution,
+    Failure,
+    Danger,
+    Bug,
+    Example,
+    Quote,
+}
+
+impl Callout {
+    #[must_use]
+    pub fn from_name(name: &str) -> Self {
+        match name.to_lowercase().as_str() {
+            "abstract" | "summary" | "tldr" => Self::Abstract,
+            "info" => Self::Info,
+            "todo" => Self::Todo,
+            "tip" | "hint" => Self::Tip,
+            "important" => Self::Important,
+            "success" | "check" | "done" => Self::Success,
+            "question" | "help" | "faq" => Self::Question,
+            "warning" | "attention" => Self::Warning,
+            "caution" => Self::Caution,
+            "failure" | "fail" | "missing" => Self::Failure,
+            "danger" | "error" => Self::Danger,
+            "bug" => Self::Bug,
+            "example" => Self::Example,
+            "quote" | "cite" => Self::Quote,
+            _ => Self::Note,
+        }
+    }
+
+    /// Name used for the kind in the config
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Note => "note",
+            Self::Abstract => "abstract",
+            Self::Info => "info",
+            Self::Todo => "todo",
+            Self::Tip => "tip",
+            Self::Important => "important",
+            Self::Success => "success",
+            Self::Question => "question",
+            Self::Warning => "warning",
+            Self::Caution => "caution",
+            Self::Failure => "failure",
+            Self::Danger => "danger",
+            Self::Bug => "bug",
+            Self::Example => "example",
+            Self::Quote => "quote",
+        }
+    }
+
+    /// Color of the quote bar and title, `quote_<name>` in the config
+    #[must_use]
+    pub fn color(self) -> Color {
+        let colors = color_config();
+        match self {
+            Self::Note => colors.quote_note,
+            Self::Abstract => colors.quote_abstract,
+            Self::Info => colors.quote_info,
+            Self::Todo => colors.quote_todo,
+            Self::Tip => colors.quote_tip,
+            Self::Important => colors.quote_important,
+            Self::Success => colors.quote_success,
+            Self::Question => colors.quote_question,
+            Self::Warning => colors.quote_warning,
+            Self::Caution => colors.quote_caution,
+            Self::Failure => colors.quote_failure,
+            Self::Danger => colors.quote_danger,
+            Self::Bug => colors.quote_bug,
+            Self::Example => colors.quote_example,
+            Self::Quote => colors.quote_quote,
+        }
+    }
+
+    /// Icon shown in front of the title. Set in the `[callout_icons]` table of
+    /// the config, an empty string leaves it out.
+    #[must_use]
+    pub fn icon(self) -> &'static str {
+        if let Some(icon) = GENERAL_CONFIG.callout_icons.get(self.name()) {
+            return icon;
+        }
+        // Nerd Font glyphs from Font Awesome
+        match self {
+            Self::Note => "\u{f040}",
+            Self::Abstract => "\u{f0ea}",
+            Self::Info => "\u{f05a}",
+            Self::Todo => "\u{f058}",
+            Self::Tip => "\u{f0eb}",
+            Self::Important => "\u{f06a}",
+            Self::Success => "\u{f00c}",
+            Self::Question => "\u{f059}",
+            Self::Warning => "\u{f071}",
+            Self::Caution => "\u{f05e}",
+            Self::Failure => "\u{f00d}",
+            Self::Danger => "\u{f0e7}",
+            Self::Bug => "\u{f188}",
+            Self::Example => "\u{f03a}",
+            Self::Quote => "\u{f10d}",
+        }
+    }
+}
@@ -10,6 +10,7 @@ pub struct ImageComponent {
     y_offset: u16,
     height: u16,
     scroll_offset: u16,
+    hidden: bool,
     image: StatefulProtocol,
 }
 
@@ -29,6 +30,7 @@ impl ImageComponent {
             _alt_text: alt_text.to_string(),
             scroll_offset: 0,
             y_offset: 0,
+            hidden: false,
         })
     }
 
@@ -52,13 +54,27 @@ impl ImageComponent {
 
     #[must_use]
     pub fn height(&self) -> u16 {
+        if self.hidden { 0 } else { self.height }
+    }
+
+    #[must_use]
+    pub fn raw_height(&self) -> u16 {
         self.height
     }
+
+    #[must_use]
+    pub fn is_hidden(&self) -> bool {
+        self.hidden
+    }
+
+    pub fn set_hidden(&mut self, hidden: bool) {
+        self.hidden = hidden;
+    }
 }
 
 impl ComponentProps for ImageComponent {
     fn height(&self) -> u16 {
-        self.height
+        ImageComponent::height(self)
     }
 
     fn set_y_offset(&mut self, y_offset: u16) {
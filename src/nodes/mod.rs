@@ -1,4 +1,6 @@
+pub mod callout;
 pub mod image;
 pub mod root;
+pub mod table;
 pub mod textcomponent;
 pub mod word;
@@ -1,17 +1,33 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
-use crate::search::{compare_heading, find_and_mark};
+use crate::{
+    parser::ParseDiagnostic,
+    search::{decode_anchor, find_and_mark},
+};
 
 use super::{
     image::ImageComponent,
     textcomponent::{TextComponent, TextNode},
-    word::{Word, WordType},
+    word::{MetaData, Word, WordType},
 };
 
+/// Which `<details>` blocks and headings are folded. Details are known by
+/// their summary and how many details before them have the same summary,
+/// headings by their anchor, so the state can be put back on the document
+/// after it is parsed again for a new width or because the file changed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FoldState {
+    details: HashMap<(String, usize), bool>,
+    headings: HashSet<String>,
+}
+
 pub struct ComponentRoot {
     file_name: Option<String>,
     components: Vec<Component>,
     is_focused: bool,
+    parse_errors: Vec<ParseDiagnostic>,
+    link_titles: HashMap<String, String>,
+    abbreviations: HashMap<String, String>,
 }
 
 impl ComponentRoot {
@@ -21,6 +37,9 @@ impl ComponentRoot {
             file_name,
             components,
             is_focused: false,
+            parse_errors: Vec::new(),
+            link_titles: HashMap::new(),
+            abbreviations: HashMap::new(),
         }
     }
 
@@ -59,6 +78,62 @@ impl ComponentRoot {
         self.file_name.as_deref()
     }
 
+    /// Grammar errors for the regions that are shown as plain text instead
+    #[must_use]
+    pub fn parse_errors(&self) -> &[ParseDiagnostic] {
+        &self.parse_errors
+    }
+
+    pub fn set_parse_errors(&mut self, errors: Vec<ParseDiagnostic>) {
+        self.parse_errors = errors;
+    }
+
+    /// Make the slugs of headings unique like GitHub does, by adding `-1`,
+    /// `-2`, … to the ones already used by an earlier heading
+    pub fn assign_heading_slugs(&mut self) {
+        let mut seen: HashMap<String, usize> = HashMap::new();
+        for anchor in self
+            .components_mut()
+            .into_iter()
+            .filter_map(TextComponent::heading_anchor_mut)
+        {
+            if anchor.kind() != WordType::MetaInfo(MetaData::HeadingSlug) {
+                seen.entry(anchor.content().to_owned()).or_insert(0);
+                continue;
+            }
+
+            let base = anchor.content().to_owned();
+            let mut slug = base.clone();
+            while seen.contains_key(&slug) {
+                let count = seen.entry(base.clone()).or_insert(0);
+                *count += 1;
+                slug = format!("{base}-{count}");
+            }
+            seen.insert(slug.clone(), 0);
+            anchor.set_content(slug);
+        }
+    }
+
+    /// The title given to a link, either inline or in its reference definition
+    #[must_use]
+    pub fn link_title(&self, url: &str) -> Option<&str> {
+        self.link_titles.get(url).map(String::as_str)
+    }
+
+    pub fn set_link_titles(&mut self, titles: HashMap<String, String>) {
+        self.link_titles = titles;
+    }
+
+    /// What an abbreviation defined with `*[label]: title` stands for
+    #[must_use]
+    pub fn abbreviation(&self, label: &str) -> Option<&str> {
+        self.abbreviations.get(label).map(String::as_str)
+    }
+
+    pub fn set_abbreviations(&mut self, abbreviations: HashMap<String, String>) {
+        self.abbreviations = abbreviations;
+    }
+
     #[must_use]
     pub fn words(&self) -> Vec<&Word> {
         self.components
@@ -133,38 +208,35 @@ impl ComponentRoot {
         }
     }
 
+    /// The footnote a reference like `[1]` points to
     #[must_use]
-    pub fn find_footnote(&self, search: &str) -> String {
-        let footnote = self
-            .components
+    pub fn footnote(&self, key: &str) -> Option<&TextComponent> {
+        self.components
             .iter()
             .filter_map(|f| match f {
-                Component::TextComponent(text_component) => {
-                    if text_component.kind() == TextNode::Footnote {
-                        Some(text_component)
-                    } else {
-                        None
-                    }
-                }
+                Component::TextComponent(comp) => Some(comp),
                 Component::Image(_) => None,
             })
-            .filter(|f| {
-                if let Some(foot_ref) = f.meta_info().iter().next() {
-                    foot_ref.content() == search
-                } else {
-                    false
-                }
-            })
-            .flat_map(|f| f.content().iter().flatten())
-            .filter(|f| f.kind() == WordType::Footnote)
-            .map(Word::content)
-            .collect::<String>();
+            .filter(|comp| comp.kind() == TextNode::Footnote)
+            .find(|comp| comp.meta_info().first().is_some_and(|w| w.content() == key))
+    }
 
-        if footnote.is_empty() {
-            String::from("Footnote not found")
-        } else {
-            footnote
+    pub fn footnote_offset(&self, key: &str) -> Result<u16, String> {
+        let mut y_offset = 0;
+        for component in &self.components {
+            match component {
+                Component::TextComponent(comp) => {
+                    if comp.kind() == TextNode::Footnote
+                        && comp.meta_info().first().is_some_and(|w| w.content() == key)
+                    {
+                        return Ok(y_offset);
+                    }
+                    y_offset += comp.height();
+                }
+                Component::Image(e) => y_offset += e.height(),
+            }
         }
+        Err(format!("Footnote not found: {key}"))
     }
 
     #[must_use]
@@ -184,7 +256,11 @@ impl ComponentRoot {
                     row.iter().for_each(|c| {
                         if matches!(
                             c.kind(),
-                            WordType::Link | WordType::Selected | WordType::FootnoteInline
+                            WordType::Link
+                                | WordType::Selected
+                                | WordType::FootnoteInline
+                                | WordType::Abbreviation
+                                | WordType::Tag
                         ) {
                             indexes.push((count, height + index as u16));
                             count += 1;
@@ -196,6 +272,53 @@ impl ComponentRoot {
         indexes
     }
 
+    /// Returns `(index, y_offset, text)` for each link starting within the
+    /// `height` lines below `scroll`, with the text of its first word. The
+    /// index is the one `select` takes.
+    #[must_use]
+    pub fn visible_links(&self, scroll: u16, height: u16) -> Vec<(usize, u16, &str)> {
+        let mut links = Vec::new();
+        let mut count = 0;
+        for comp in self.components.iter().filter_map(|f| match f {
+            Component::TextComponent(comp) => Some(comp),
+            Component::Image(_) => None,
+        }) {
+            // The lines of a table are its cells, start looking at its top
+            let is_table = matches!(comp.kind(), TextNode::Table(..));
+            for group in comp.link_groups() {
+                let (line, word) = group[0];
+                let y = comp.y_offset() + if is_table { 0 } else { line as u16 };
+                if (scroll..scroll.saturating_add(height)).contains(&y) {
+                    links.push((count, y, word.content()));
+                }
+                count += 1;
+            }
+        }
+        links
+    }
+
+    /// Returns `(y_offset, word, target)` for each word of the links to
+    /// other places, web pages or files, in document order
+    #[must_use]
+    pub fn link_words(&self) -> Vec<(u16, &Word, &str)> {
+        let mut words = Vec::new();
+        for comp in self.components.iter().filter_map(|f| match f {
+            Component::TextComponent(comp) => Some(comp),
+            Component::Image(_) => None,
+        }) {
+            let is_table = matches!(comp.kind(), TextNode::Table(..));
+            for (group, target) in comp.link_groups().into_iter().zip(comp.link_targets()) {
+                for (line, word) in group {
+                    if matches!(word.kind(), WordType::Link | WordType::Selected) {
+                        let y = comp.y_offset() + if is_table { 0 } else { line as u16 };
+                        words.push((y, word, target));
+                    }
+                }
+            }
+        }
+        words
+    }
+
     /// Sets the y offset of the components
     pub fn set_scroll(&mut self, scroll: u16) {
         let mut y_offset = 0;
@@ -206,22 +329,58 @@ impl ComponentRoot {
         }
     }
 
-    pub fn heading_offset(&self, heading: &str) -> Result<u16, String> {
-        let mut y_offset = 0;
-        for component in &self.components {
-            match component {
-                Component::TextComponent(comp) => {
+    /// The line of the heading with the anchor `heading`. The sections and
+    /// `<details>` blocks it is folded into are opened first.
+    pub fn heading_offset(&mut self, heading: &str) -> Result<u16, String> {
+        // Anchors match whatever their case, like GitHub does it
+        let anchor = decode_anchor(heading.strip_prefix('#').unwrap_or(heading)).to_lowercase();
+        let index = self
+            .components
+            .iter()
+            .position(|c| {
+                matches!(c, Component::TextComponent(comp)
                     if comp.kind() == TextNode::Heading
-                        && compare_heading(&heading[1..], comp.content())
-                    {
-                        return Ok(y_offset);
-                    }
-                    y_offset += comp.height();
-                }
-                Component::Image(e) => y_offset += e.height(),
+                        && comp
+                            .heading_anchor()
+                            .is_some_and(|a| a.to_lowercase() == anchor))
+            })
+            .ok_or_else(|| format!("Heading not found: {heading}"))?;
+        self.unfold_to(index);
+        Ok(self.offset_of(index))
+    }
+
+    /// Open the folded sections and `<details>` blocks the component at
+    /// `index` is in
+    fn unfold_to(&mut self, index: usize) {
+        let Component::TextComponent(target) = &self.components[index] else {
+            return;
+        };
+        let details = target.owning_details_ids().to_vec();
+        // A heading's section holds the target until a heading of its level
+        // or above
+        let mut level = target
+            .is_foldable_heading()
+            .then(|| target.heading_level())
+            .flatten()
+            .unwrap_or(u8::MAX);
+        for c in self.components[..index].iter_mut().rev() {
+            let Component::TextComponent(tc) = c else {
+                continue;
+            };
+            if tc.is_foldable_heading()
+                && let Some(heading_level) = tc.heading_level()
+                && heading_level < level
+            {
+                tc.set_section_folded(false);
+                level = heading_level;
+            }
+            if let TextNode::DetailsSummary { id, .. } = tc.kind()
+                && details.contains(&id)
+            {
+                tc.set_details_folded(false);
             }
         }
-        Err(format!("Heading not found: {heading}"))
+        self.recompute_visibility();
     }
 
     /// Return the content of the components, where each element a line
@@ -281,18 +440,36 @@ impl ComponentRoot {
         let mut iter = self.components.into_iter().peekable();
         while let Some(component) = iter.next() {
             let kind = component.kind();
-            let curr_ids: Vec<u32> = match &component {
-                Component::TextComponent(tc) => tc.owning_details_ids().to_vec(),
-                Component::Image(_) => Vec::new(),
+            let (curr_ids, curr_containers) = match &component {
+                Component::TextComponent(tc) => {
+                    (tc.owning_details_ids().to_vec(), tc.containers().to_vec())
+                }
+                Component::Image(_) => (Vec::new(), Vec::new()),
             };
             components.push(component);
+            // Footnotes are listed without blank lines between them, and
+            // callout titles and the borders of embeds are drawn right around
+            // the body
             if let Some(next) = iter.peek()
                 && kind != TextNode::LineBreak
                 && next.kind() != TextNode::LineBreak
+                && !(kind == TextNode::Footnote && next.kind() == TextNode::Footnote)
+                && !matches!(
+                    kind,
+                    TextNode::CalloutTitle(_)
+                        | TextNode::EmbedTitle
+                        | TextNode::DetailsSummary {
+                            callout: Some(_),
+                            ..
+                        }
+                )
+                && next.kind() != TextNode::EmbedEnd
             {
-                let next_ids: Vec<u32> = match next {
-                    Component::TextComponent(tc) => tc.owning_details_ids().to_vec(),
-                    Component::Image(_) => Vec::new(),
+                let (next_ids, next_containers) = match next {
+                    Component::TextComponent(tc) => {
+                        (tc.owning_details_ids().to_vec(), tc.containers().to_vec())
+                    }
+                    Component::Image(_) => (Vec::new(), Vec::new()),
                 };
                 // An inserted LineBreak inherits the longest common
                 // outermost prefix of its two neighbors' owning-details
@@ -304,8 +481,17 @@ impl ComponentRoot {
                     .take_while(|(a, b)| a == b)
                     .map(|(a, _)| *a)
                     .collect();
+                // Likewise it stays inside the list items and quotes both
+                // neighbors are nested in, so quote bars are drawn through it
+                let shared_containers = curr_containers
+                    .iter()
+                    .zip(next_containers.iter())
+                    .take_while(|(a, b)| a == b)
+                    .map(|(a, _)| *a)
+                    .collect();
                 let mut lb = TextComponent::new(TextNode::LineBreak, Vec::new());
                 lb.set_owning_details_ids(shared_ids);
+                lb.set_containers(shared_containers);
                 components.push(Component::TextComponent(lb));
             }
         }
@@ -313,6 +499,9 @@ impl ComponentRoot {
             file_name: self.file_name,
             components,
             is_focused: self.is_focused,
+            parse_errors: self.parse_errors,
+            link_titles: self.link_titles,
+            abbreviations: self.abbreviations,
         }
     }
 
@@ -354,12 +543,210 @@ impl ComponentRoot {
             })
             .collect();
 
-        for c in self.components.iter_mut() {
-            if let Component::TextComponent(tc) = c {
-                let hidden = tc.owning_details_ids().iter().any(|id| folded.contains(id));
-                tc.set_hidden(hidden);
+        // The folded heading whose section is being walked, with its level,
+        // and the lines hidden under it so far
+        let mut section: Option<(usize, u8)> = None;
+        let mut lines = 0;
+        let mut folded_lines = Vec::new();
+        for (i, c) in self.components.iter_mut().enumerate() {
+            let level = match c {
+                Component::TextComponent(tc) if tc.is_foldable_heading() => tc.heading_level(),
+                _ => None,
+            };
+            if let (Some(level), Some((heading, folded_level))) = (level, section)
+                && level <= folded_level
+            {
+                folded_lines.push((heading, std::mem::take(&mut lines)));
+                section = None;
+            }
+
+            match c {
+                Component::TextComponent(tc) => {
+                    let in_details = tc.owning_details_ids().iter().any(|id| folded.contains(id));
+                    tc.set_hidden(in_details || section.is_some());
+                    if section.is_some() && !in_details {
+                        lines += tc.raw_height();
+                    }
+                    if let Some(level) = level
+                        && section.is_none()
+                        && tc.is_section_folded()
+                    {
+                        section = Some((i, level));
+                    }
+                }
+                Component::Image(img) => {
+                    img.set_hidden(section.is_some());
+                    if section.is_some() {
+                        lines += img.raw_height();
+                    }
+                }
             }
         }
+        if let Some((heading, _)) = section {
+            folded_lines.push((heading, lines));
+        }
+
+        for (heading, lines) in folded_lines {
+            if let Some(Component::TextComponent(tc)) = self.components.get_mut(heading) {
+                tc.set_folded_lines(lines);
+            }
+        }
+    }
+
+    /// Fold the sections under headings of `level` and deeper and open the
+    /// ones above it, or open all of them with `None`. Returns where to scroll
+    /// to keep the line at `scroll` in view, or the heading it was folded
+    /// under.
+    pub fn fold_headings(&mut self, level: Option<u8>, scroll: u16) -> u16 {
+        self.refold(scroll, |tc| {
+            if tc.is_foldable_heading() {
+                let folded = level.is_some_and(|level| tc.heading_level() >= Some(level));
+                tc.set_section_folded(folded);
+            }
+        })
+    }
+
+    /// Fold or open every section, `<details>` block and foldable callout.
+    /// Returns where to scroll to like `fold_headings`.
+    pub fn fold_all(&mut self, folded: bool, scroll: u16) -> u16 {
+        self.refold(scroll, |tc| {
+            if tc.is_foldable_heading() {
+                tc.set_section_folded(folded);
+            }
+            tc.set_details_folded(folded);
+        })
+    }
+
+    fn refold(&mut self, scroll: u16, fold: impl Fn(&mut TextComponent)) -> u16 {
+        if self.components.is_empty() {
+            return 0;
+        }
+        let anchor = self.component_at(scroll);
+        for tc in self.components_mut() {
+            fold(tc);
+        }
+        self.recompute_visibility();
+
+        let visible = (0..=anchor)
+            .rev()
+            .find(|i| !is_hidden(&self.components[*i]))
+            .unwrap_or(0);
+        self.offset_of(visible)
+    }
+
+    /// Fold or open the section the line at `scroll` is in, under the closest
+    /// heading above it. Returns the offset of that heading.
+    pub fn toggle_section_at(&mut self, scroll: u16) -> Result<u16, String> {
+        let anchor = self.component_at(scroll);
+        let heading = self
+            .components
+            .iter()
+            .take(anchor + 1)
+            .rposition(|c| {
+                matches!(c, Component::TextComponent(tc)
+                    if tc.is_foldable_heading() && !tc.is_hidden())
+            })
+            .ok_or("No heading to fold")?;
+        if let Component::TextComponent(tc) = &mut self.components[heading] {
+            tc.set_section_folded(!tc.is_section_folded());
+        }
+        self.recompute_visibility();
+        Ok(self.offset_of(heading))
+    }
+
+    /// The code block at the top of the `height` lines below `scroll`, else
+    /// the first one below it in those lines
+    #[must_use]
+    pub fn code_block_at(&self, scroll: u16, height: u16) -> Option<&TextComponent> {
+        let mut offset = 0;
+        for c in &self.components {
+            let start = offset;
+            offset += c.height();
+            if start >= scroll.saturating_add(height) {
+                break;
+            }
+            if let Component::TextComponent(tc) = c
+                && offset > scroll
+                && !tc.is_hidden()
+                && tc.kind() == TextNode::CodeBlock
+            {
+                return Some(tc);
+            }
+        }
+        None
+    }
+
+    /// The anchor of the heading of the section line `y` is in
+    #[must_use]
+    pub fn section_anchor(&self, y: u16) -> Option<&str> {
+        self.components
+            .iter()
+            .take(self.component_at(y) + 1)
+            .rev()
+            .find_map(|c| match c {
+                Component::TextComponent(tc)
+                    if tc.kind() == TextNode::Heading && !tc.is_hidden() =>
+                {
+                    tc.heading_anchor()
+                }
+                _ => None,
+            })
+    }
+
+    #[must_use]
+    pub fn fold_state(&self) -> FoldState {
+        let mut state = FoldState::default();
+        let mut counts = HashMap::new();
+        for tc in self.components() {
+            if let TextNode::DetailsSummary { folded, .. } = tc.kind() {
+                state.details.insert(details_key(tc, &mut counts), folded);
+            } else if tc.is_foldable_heading()
+                && tc.is_section_folded()
+                && let Some(anchor) = tc.heading_anchor()
+            {
+                state.headings.insert(anchor.to_owned());
+            }
+        }
+        state
+    }
+
+    /// Fold the details and headings like they were in `state`. Details
+    /// that weren't in the document then keep how they were written.
+    pub fn restore_fold_state(&mut self, state: &FoldState) {
+        let mut counts = HashMap::new();
+        for tc in self.components_mut() {
+            if matches!(tc.kind(), TextNode::DetailsSummary { .. }) {
+                if let Some(folded) = state.details.get(&details_key(tc, &mut counts)) {
+                    tc.set_details_folded(*folded);
+                }
+            } else if tc.is_foldable_heading() {
+                let folded = tc
+                    .heading_anchor()
+                    .is_some_and(|anchor| state.headings.contains(anchor));
+                tc.set_section_folded(folded);
+            }
+        }
+        self.recompute_visibility();
+    }
+
+    /// Index of the visible component at line `y`, or the last one
+    fn component_at(&self, y: u16) -> usize {
+        let mut offset = 0;
+        for (i, c) in self.components.iter().enumerate() {
+            offset += c.height();
+            if !is_hidden(c) && offset > y {
+                return i;
+            }
+        }
+        self.components.len().saturating_sub(1)
+    }
+
+    /// Line the component at `index` starts on
+    fn offset_of(&self, index: usize) -> u16 {
+        self.components[..index]
+            .iter()
+            .map(ComponentProps::height)
+            .sum()
     }
 
     /// Count of `<details>` summary headers that are currently *visible*
@@ -451,6 +838,72 @@ impl ComponentRoot {
         self.recompute_visibility();
         Ok(())
     }
+
+    /// Returns `(index, y_offset)` for each visible table, in document
+    /// order, like `details_index_and_height`.
+    #[must_use]
+    pub fn table_index_and_height(&self) -> Vec<(usize, u16)> {
+        self.components()
+            .into_iter()
+            .filter(|comp| !comp.is_hidden() && matches!(comp.kind(), TextNode::Table(..)))
+            .enumerate()
+            .map(|(i, comp)| (i, comp.y_offset()))
+            .collect()
+    }
+
+    /// Put the `index`-th visible table in table mode, returning its
+    /// `y_offset`. Any other table leaves table mode.
+    pub fn select_table(&mut self, index: usize, width: u16) -> Result<u16, String> {
+        self.deselect_table(width);
+        let table = self
+            .components_mut()
+            .into_iter()
+            .filter(|comp| !comp.is_hidden() && matches!(comp.kind(), TextNode::Table(..)))
+            .nth(index)
+            .ok_or(format!("Table index out of bounds: {index}"))?;
+        table.focus_table();
+        Ok(table.y_offset())
+    }
+
+    /// Leave table mode, showing the table as written again
+    pub fn deselect_table(&mut self, width: u16) {
+        for comp in self.components_mut() {
+            if comp.table_view().is_some() {
+                comp.unfocus_table(width);
+            }
+        }
+    }
+
+    /// The table in table mode
+    #[must_use]
+    pub fn selected_table(&self) -> Option<&TextComponent> {
+        self.components()
+            .into_iter()
+            .find(|comp| comp.table_view().is_some())
+    }
+
+    pub fn selected_table_mut(&mut self) -> Option<&mut TextComponent> {
+        self.components_mut()
+            .into_iter()
+            .find(|comp| comp.table_view().is_some())
+    }
+}
+
+/// The summary of a details block, however it is wrapped, and how many
+/// details before it have the same summary, counted in `counts`
+fn details_key(summary: &TextComponent, counts: &mut HashMap<String, usize>) -> (String, usize) {
+    let text = summary.content_as_lines().join(" ");
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    let count = counts.entry(text.clone()).or_insert(0);
+    *count += 1;
+    (text, *count - 1)
+}
+
+fn is_hidden(component: &Component) -> bool {
+    match component {
+        Component::TextComponent(comp) => comp.is_hidden(),
+        Component::Image(img) => img.is_hidden(),
+    }
 }
 
 pub trait ComponentProps {
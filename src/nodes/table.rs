@@ -0,0 +1,232 @@
+//! Table mode. A table keeps its cells as parsed, so they can be sorted,
+//! filtered and exported regardless of how they were wrapped to fit the
+//! screen.
+
+use std::cmp::Ordering;
+
+use itertools::Itertools;
+
+use super::word::{Word, WordType};
+
+/// The cursor, sort order and filter of a table in table mode
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TableView {
+    /// Row of the cursor among the shown rows, the header is row 0
+    pub row: usize,
+    pub column: usize,
+    pub sort: Option<Sort>,
+    pub filter: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sort {
+    Ascending(usize),
+    Descending(usize),
+}
+
+impl Sort {
+    /// Sorting a column again reverses it, and a third time goes back to the
+    /// order of the document
+    #[must_use]
+    pub fn next(sort: Option<Self>, column: usize) -> Option<Self> {
+        match sort {
+            Some(Self::Ascending(c)) if c == column => Some(Self::Descending(column)),
+            Some(Self::Descending(c)) if c == column => None,
+            _ => Some(Self::Ascending(column)),
+        }
+    }
+
+    #[must_use]
+    pub fn column(self) -> usize {
+        match self {
+            Self::Ascending(column) | Self::Descending(column) => column,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Delimiter {
+    Comma,
+    Tab,
+}
+
+impl Delimiter {
+    /// TSV for `.tsv` and `.tab` files, CSV otherwise
+    #[must_use]
+    pub fn from_path(path: &str) -> Self {
+        let lowercase = path.to_lowercase();
+        if lowercase.ends_with(".tsv") || lowercase.ends_with(".tab") {
+            Self::Tab
+        } else {
+            Self::Comma
+        }
+    }
+
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Comma => "CSV",
+            Self::Tab => "TSV",
+        }
+    }
+}
+
+/// The text of a cell, without the markup
+#[must_use]
+pub fn cell_text(cell: &[Word]) -> String {
+    cell.iter()
+        .filter(|w| w.kind() != WordType::HardBreak)
+        .map(Word::content)
+        .collect::<String>()
+        .trim()
+        .to_owned()
+}
+
+/// The body rows to show, in the order to show them in
+#[must_use]
+pub fn visible_rows(rows: &[&[Vec<Word>]], view: &TableView) -> Vec<usize> {
+    let filter = view.filter.to_lowercase();
+    let mut visible: Vec<usize> = (0..rows.len())
+        .filter(|i| {
+            filter.is_empty()
+                || rows[*i]
+                    .iter()
+                    .any(|cell| cell_text(cell).to_lowercase().contains(&filter))
+        })
+        .collect();
+
+    if let Some(sort) = view.sort {
+        let key = |i: &usize| {
+            rows[*i]
+                .get(sort.column())
+                .map(|cell| cell_text(cell))
+                .unwrap_or_default()
+        };
+        // A stable sort keeps rows with the same value in document order
+        visible.sort_by(|a, b| {
+            let ordering = compare_cells(&key(a), &key(b));
+            match sort {
+                Sort::Ascending(_) => ordering,
+                Sort::Descending(_) => ordering.reverse(),
+            }
+        });
+    }
+    visible
+}
+
+/// Numbers compare by value, `1,234.5`, `-3`, `12%` and `$9` included, and
+/// before text. Text compares case-insensitively with the numbers inside it
+/// by value, so `v2` comes before `v10`.
+#[must_use]
+pub fn compare_cells(a: &str, b: &str) -> Ordering {
+    match (number(a), number(b)) {
+        (Some(x), Some(y)) => x.total_cmp(&y),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => natural_cmp(&a.to_lowercase(), &b.to_lowercase()),
+    }
+}
+
+fn number(text: &str) -> Option<f64> {
+    let text = text
+        .trim()
+        .trim_start_matches(['$', '€', '£', '¥'])
+        .trim_end_matches('%')
+        .replace([',', '_'], "");
+    if text.is_empty() || text.chars().any(char::is_alphabetic) {
+        return None;
+    }
+    text.parse().ok()
+}
+
+fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let chunks = |s: &str| {
+        s.chars()
+            .chunk_by(char::is_ascii_digit)
+            .into_iter()
+            .map(|(digits, chunk)| (digits, chunk.collect::<String>()))
+            .collect::<Vec<_>>()
+    };
+
+    for (x, y) in chunks(a).into_iter().zip(chunks(b)) {
+        let ordering = match (x, y) {
+            ((true, x), (true, y)) => {
+                let (x, y) = (x.trim_start_matches('0'), y.trim_start_matches('0'));
+                x.len().cmp(&y.len()).then_with(|| x.cmp(y))
+            }
+            ((_, x), (_, y)) => x.cmp(&y),
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+    a.len().cmp(&b.len())
+}
+
+/// Rows of cell texts as CSV, quoted where needed, or as TSV
+#[must_use]
+pub fn to_delimited(rows: &[Vec<String>], delimiter: Delimiter) -> String {
+    let mut out = String::new();
+    for row in rows {
+        let line = row
+            .iter()
+            .map(|cell| match delimiter {
+                Delimiter::Comma if cell.contains([',', '"', '\n']) => {
+                    format!("\"{}\"", cell.replace('"', "\"\""))
+                }
+                Delimiter::Comma => cell.clone(),
+                Delimiter::Tab => cell.replace(['\t', '\n'], " "),
+            })
+            .join(match delimiter {
+                Delimiter::Comma => ",",
+                Delimiter::Tab => "\t",
+            });
+        out.push_str(&line);
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn numbers_sort_by_value() {
+        let mut cells = vec!["10", "9", "1,200", "-3", "n/a", "2.5%", "$4"];
+        cells.sort_by(|a, b| compare_cells(a, b));
+        assert_eq!(cells, ["-3", "2.5%", "$4", "9", "10", "1,200", "n/a"]);
+    }
+
+    #[test]
+    fn text_sorts_naturally() {
+        let mut cells = vec!["v10", "V2", "v1", "alpha"];
+        cells.sort_by(|a, b| compare_cells(a, b));
+        assert_eq!(cells, ["alpha", "v1", "V2", "v10"]);
+    }
+
+    #[test]
+    fn sorting_a_column_cycles() {
+        let sort = Sort::next(None, 1);
+        assert_eq!(sort, Some(Sort::Ascending(1)));
+        assert_eq!(Sort::next(sort, 1), Some(Sort::Descending(1)));
+        assert_eq!(Sort::next(Some(Sort::Descending(1)), 1), None);
+        assert_eq!(Sort::next(sort, 2), Some(Sort::Ascending(2)));
+    }
+
+    #[test]
+    fn csv_quotes_when_needed() {
+        let rows = vec![
+            vec!["name".to_owned(), "note".to_owned()],
+            vec!["a, b".to_owned(), "say \"hi\"".to_owned()],
+        ];
+        assert_eq!(
+            to_delimited(&rows, Delimiter::Comma),
+            "name,note\n\"a, b\",\"say \"\"hi\"\"\"\n"
+        );
+        assert_eq!(
+            to_delimited(&rows, Delimiter::Tab),
+            "name\tnote\na, b\tsay \"hi\"\n"
+        );
+    }
+}
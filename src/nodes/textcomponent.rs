@@ -4,16 +4,19 @@ use itertools::Itertools;
 use mermaid_text::render_with_width;
 use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
-use ratatui::style::Color;
+use ratatui::{layout::Alignment, style::Color};
 use tree_sitter_highlight::HighlightEvent;
 
 use crate::{
     highlight::{COLOR_MAP, HighlightInfo, highlight_code},
-    nodes::word::MetaData,
+    nodes::{callout::Callout, word::MetaData},
     util::general::GENERAL_CONFIG,
 };
 
-use super::word::{Word, WordType};
+use super::{
+    table::{self, Sort, TableView},
+    word::{Word, WordType},
+};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum TextNode {
@@ -24,20 +27,53 @@ pub enum TextNode {
     Task,
     List,
     Footnote,
-    /// (`widths_by_column`, `heights_by_row`)
-    Table(Vec<u16>, Vec<u16>),
+    /// (`widths_by_column`, `heights_by_row`, `alignment_by_column`)
+    Table(Vec<u16>, Vec<u16>, Vec<Alignment>),
     CodeBlock,
     Quote,
     HorizontalSeparator,
+    /// Whether each line is a term, the other lines are definitions
+    DefinitionList(Vec<bool>),
     DetailsSummary {
         id: u32,
         folded: bool,
         body_len: usize,
+        /// Set for the title of a foldable callout
+        callout: Option<Callout>,
     },
+    /// Title of a callout that can't be folded
+    CalloutTitle(Callout),
+    /// Top border of an embedded note, with the note's name in it
+    EmbedTitle,
+    /// Bottom border of an embedded note
+    EmbedEnd,
 }
 
 pub(crate) const TABLE_CELL_PADDING: u16 = 1;
 
+/// A block a component is nested in. List items indent their content, quotes
+/// draw a bar in front of it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Container {
+    /// Indented by the width of the list item's marker
+    ListItem(u16),
+    /// Quote, with the kind of callout if it is one
+    Quote(Option<Callout>),
+    /// Note embedded with `![[note]]`, which has a border in front of it
+    Embed,
+}
+
+impl Container {
+    /// Columns taken up in front of the nested content
+    #[must_use]
+    pub fn width(self) -> u16 {
+        match self {
+            Container::ListItem(indent) => indent,
+            Container::Quote(_) | Container::Embed => 2,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct TextComponent {
     kind: TextNode,
@@ -49,7 +85,15 @@ pub struct TextComponent {
     focused: bool,
     focused_index: usize,
     owning_details_ids: Vec<u32>,
+    containers: Vec<Container>,
     hidden: bool,
+    /// Lines hidden under a folded heading, `None` while its section is open
+    folded_lines: Option<u16>,
+    /// The code of a code block as written, kept when it is highlighted
+    code: Option<String>,
+    /// The cells of a table as parsed, row by row
+    table_cells: Vec<Vec<Word>>,
+    table_view: Option<TableView>,
 }
 
 impl TextComponent {
@@ -57,7 +101,7 @@ impl TextComponent {
     pub fn new(kind: TextNode, content: Vec<Word>) -> Self {
         let meta_info: Vec<Word> = content
             .iter()
-            .filter(|c| !c.is_renderable() || c.kind() == WordType::FootnoteInline)
+            .filter(|c| !c.is_renderable() || is_selectable_text(c))
             .cloned()
             .collect();
 
@@ -73,7 +117,12 @@ impl TextComponent {
             focused: false,
             focused_index: 0,
             owning_details_ids: Vec::new(),
+            containers: Vec::new(),
             hidden: false,
+            folded_lines: None,
+            code: None,
+            table_cells: Vec::new(),
+            table_view: None,
         }
     }
 
@@ -92,7 +141,7 @@ impl TextComponent {
             content
                 .iter()
                 .flatten()
-                .filter(|c| !c.is_renderable())
+                .filter(|c| !c.is_renderable() || is_selectable_text(c))
                 .cloned(),
         );
 
@@ -101,6 +150,12 @@ impl TextComponent {
             .map(|c| c.into_iter().filter(Word::is_renderable).collect())
             .collect();
 
+        let table_cells = if matches!(kind, TextNode::Table(..)) {
+            content.clone()
+        } else {
+            Vec::new()
+        };
+
         Self {
             kind,
             height: content.len() as u16,
@@ -111,7 +166,12 @@ impl TextComponent {
             focused: false,
             focused_index: 0,
             owning_details_ids: Vec::new(),
+            containers: Vec::new(),
             hidden: false,
+            folded_lines: None,
+            code: None,
+            table_cells,
+            table_view: None,
         }
     }
 
@@ -127,7 +187,7 @@ impl TextComponent {
 
     #[must_use]
     pub fn content_as_lines(&self) -> Vec<String> {
-        if let TextNode::Table(widths, _) = self.kind() {
+        if let TextNode::Table(widths, _, _) = self.kind() {
             let column_count = widths.len();
 
             let moved_content = self.content.chunks(column_count).collect::<Vec<_>>();
@@ -154,7 +214,10 @@ impl TextComponent {
     #[must_use]
     pub fn content_as_bytes(&self) -> Vec<u8> {
         match self.kind() {
-            TextNode::CodeBlock => self.content_as_lines().join("").as_bytes().to_vec(),
+            TextNode::CodeBlock => match &self.code {
+                Some(code) => code.as_bytes().to_vec(),
+                None => self.content_as_lines().join("").as_bytes().to_vec(),
+            },
             _ => {
                 let strings = self.content_as_lines();
                 let string = strings.join("\n");
@@ -173,6 +236,58 @@ impl TextComponent {
         &self.meta_info
     }
 
+    /// The anchor links to this heading use, its explicit `{#id}` or its slug
+    #[must_use]
+    pub fn heading_anchor(&self) -> Option<&str> {
+        self.meta_info
+            .iter()
+            .find(|w| is_heading_anchor(w))
+            .map(Word::content)
+    }
+
+    pub fn heading_anchor_mut(&mut self) -> Option<&mut Word> {
+        self.meta_info.iter_mut().find(|w| is_heading_anchor(w))
+    }
+
+    #[must_use]
+    pub fn heading_level(&self) -> Option<u8> {
+        if self.kind != TextNode::Heading {
+            return None;
+        }
+        self.meta_info.iter().find_map(|w| match w.kind() {
+            WordType::MetaInfo(MetaData::HeadingLevel(level)) => Some(level),
+            _ => None,
+        })
+    }
+
+    /// Headings of the document itself fold the section under them, not
+    /// headings in quotes, list items or embedded notes
+    #[must_use]
+    pub fn is_foldable_heading(&self) -> bool {
+        self.kind == TextNode::Heading && self.containers.is_empty()
+    }
+
+    #[must_use]
+    pub fn is_section_folded(&self) -> bool {
+        self.folded_lines.is_some()
+    }
+
+    /// How many lines the folded section under this heading takes up
+    #[must_use]
+    pub fn folded_lines(&self) -> Option<u16> {
+        self.folded_lines
+    }
+
+    pub fn set_section_folded(&mut self, folded: bool) {
+        self.folded_lines = folded.then_some(self.folded_lines.unwrap_or(0));
+    }
+
+    pub fn set_folded_lines(&mut self, lines: u16) {
+        if self.folded_lines.is_some() {
+            self.folded_lines = Some(lines);
+        }
+    }
+
     #[must_use]
     pub fn height(&self) -> u16 {
         if self.hidden { 0 } else { self.height }
@@ -196,6 +311,27 @@ impl TextComponent {
         self.owning_details_ids = ids;
     }
 
+    /// The list items and quotes this component is nested in, outermost
+    /// first.
+    #[must_use]
+    pub fn containers(&self) -> &[Container] {
+        &self.containers
+    }
+
+    pub fn prepend_container(&mut self, container: Container) {
+        self.containers.insert(0, container);
+    }
+
+    pub fn set_containers(&mut self, containers: Vec<Container>) {
+        self.containers = containers;
+    }
+
+    /// Columns taken up by the containers in front of the content
+    #[must_use]
+    pub fn containers_width(&self) -> u16 {
+        self.containers.iter().map(|c| c.width()).sum()
+    }
+
     #[must_use]
     pub fn is_hidden(&self) -> bool {
         self.hidden
@@ -213,12 +349,14 @@ impl TextComponent {
             id,
             folded: _,
             body_len,
+            callout,
         } = self.kind.clone()
         {
             self.kind = TextNode::DetailsSummary {
                 id,
                 folded,
                 body_len,
+                callout,
             };
             Some(folded)
         } else {
@@ -274,6 +412,126 @@ impl TextComponent {
         self.focused = false;
     }
 
+    /// Put a table in table mode, with the cursor on its first cell
+    pub fn focus_table(&mut self) {
+        self.focused = true;
+        self.table_view = Some(TableView::default());
+    }
+
+    /// Leave table mode and show the table as written again
+    pub fn unfocus_table(&mut self, width: u16) {
+        self.focused = false;
+        if self.table_view.take().is_some() {
+            self.content.clone_from(&self.table_cells);
+            self.transform(width);
+        }
+    }
+
+    #[must_use]
+    pub fn table_view(&self) -> Option<&TableView> {
+        self.table_view.as_ref()
+    }
+
+    fn table_column_count(&self) -> usize {
+        self.meta_info
+            .iter()
+            .filter(|w| w.kind() == WordType::MetaInfo(MetaData::ColumnsCount))
+            .count()
+    }
+
+    /// Move the cursor by rows and columns, staying inside the table
+    pub fn move_table_cursor(&mut self, rows: isize, columns: isize) {
+        let column_count = self.table_column_count();
+        let row_count = self.content.len() / column_count.max(1);
+        if let Some(view) = &mut self.table_view {
+            view.row = view
+                .row
+                .saturating_add_signed(rows)
+                .min(row_count.saturating_sub(1));
+            view.column = view
+                .column
+                .saturating_add_signed(columns)
+                .min(column_count.saturating_sub(1));
+        }
+    }
+
+    /// Lines from the top of the table to the row of the cursor
+    #[must_use]
+    pub fn table_cursor_height(&self) -> Option<u16> {
+        let (TextNode::Table(_, heights, _), Some(view)) = (&self.kind, &self.table_view) else {
+            return None;
+        };
+        Some(1 + heights.iter().take(view.row).sum::<u16>() + u16::from(view.row > 0))
+    }
+
+    /// Sort the rows by the column of the cursor, see [`Sort::next`]
+    pub fn sort_table(&mut self, width: u16) {
+        if let Some(view) = &mut self.table_view {
+            view.sort = Sort::next(view.sort, view.column);
+            self.apply_table_view(width);
+        }
+    }
+
+    /// Only show the rows with a cell containing the filter
+    pub fn filter_table(&mut self, filter: &str, width: u16) {
+        if let Some(view) = &mut self.table_view {
+            filter.clone_into(&mut view.filter);
+            self.apply_table_view(width);
+        }
+    }
+
+    /// The text of the cells of the shown rows, header included
+    #[must_use]
+    pub fn table_rows(&self) -> Vec<Vec<String>> {
+        let column_count = self.table_column_count();
+        if column_count == 0 {
+            return Vec::new();
+        }
+        let rows: Vec<&[Vec<Word>]> = self.table_cells.chunks(column_count).collect();
+        let Some((header, body)) = rows.split_first() else {
+            return Vec::new();
+        };
+        let order = match &self.table_view {
+            Some(view) => table::visible_rows(body, view),
+            None => (0..body.len()).collect(),
+        };
+        std::iter::once(*header)
+            .chain(order.into_iter().map(|i| body[i]))
+            .map(|row| row.iter().map(|cell| table::cell_text(cell)).collect())
+            .collect()
+    }
+
+    fn apply_table_view(&mut self, width: u16) {
+        let column_count = self.table_column_count();
+        let Some(view) = &mut self.table_view else {
+            return;
+        };
+        if column_count == 0 {
+            return;
+        }
+        let rows: Vec<&[Vec<Word>]> = self.table_cells.chunks(column_count).collect();
+        let Some((header, body)) = rows.split_first() else {
+            return;
+        };
+        let order = table::visible_rows(body, view);
+
+        let mut content = header.to_vec();
+        if let Some(sort) = view.sort
+            && let Some(cell) = content.get_mut(sort.column())
+        {
+            let arrow = match sort {
+                Sort::Ascending(_) => " ▲",
+                Sort::Descending(_) => " ▼",
+            };
+            cell.push(Word::new(arrow.to_owned(), WordType::Normal));
+        }
+        view.row = view.row.min(order.len());
+        content.extend(order.into_iter().flat_map(|i| body[i].iter().cloned()));
+
+        self.content = content;
+        self.transform(width);
+    }
+
     pub fn visually_select(&mut self, index: usize) -> Result<(), String> {
         self.focused = true;
         self.focused_index = index;
@@ -301,13 +559,25 @@ impl TextComponent {
         let mut selection: Vec<Vec<&mut Word>> = Vec::new();
         let mut iter = self.content.iter_mut().flatten().peekable();
         while let Some(e) = iter.peek() {
-            if matches!(e.kind(), WordType::Link | WordType::FootnoteInline) {
+            // A tag is a single word, even right next to another one
+            if e.kind() == WordType::Tag {
+                selection.extend(iter.next().map(|word| vec![word]));
+            } else if matches!(
+                e.kind(),
+                WordType::Link | WordType::FootnoteInline | WordType::Abbreviation
+            ) {
+                // Stop before the next word rather than after it, a tag right
+                // after a link is selected on its own
                 selection.push(
-                    iter.by_ref()
-                        .take_while(|c| {
-                            matches!(c.kind(), WordType::Link | WordType::FootnoteInline)
+                    std::iter::from_fn(|| {
+                        iter.next_if(|c| {
+                            matches!(
+                                c.kind(),
+                                WordType::Link | WordType::FootnoteInline | WordType::Abbreviation
+                            )
                         })
-                        .collect(),
+                    })
+                    .collect(),
                 );
             } else {
                 iter.next();
@@ -316,22 +586,52 @@ impl TextComponent {
         selection
     }
 
+    /// The words of each link with the line they are on, grouped like
+    /// `link_words_mut`
     #[must_use]
-    pub fn get_footnote(&self, search: &str) -> String {
-        self.content()
+    pub fn link_groups(&self) -> Vec<Vec<(usize, &Word)>> {
+        let mut groups: Vec<Vec<(usize, &Word)>> = Vec::new();
+        if self.hidden {
+            return groups;
+        }
+        let mut in_link = false;
+        for (line, row) in self.content.iter().enumerate() {
+            for word in row {
+                match word.kind() {
+                    WordType::Tag => {
+                        groups.push(vec![(line, word)]);
+                        in_link = false;
+                    }
+                    WordType::Link
+                    | WordType::Selected
+                    | WordType::FootnoteInline
+                    | WordType::Abbreviation => {
+                        match groups.last_mut() {
+                            Some(group) if in_link => group.push((line, word)),
+                            _ => groups.push(vec![(line, word)]),
+                        }
+                        in_link = true;
+                    }
+                    _ => in_link = false,
+                }
+            }
+        }
+        groups
+    }
+
+    /// Where each link goes, in the order of `link_groups`
+    pub fn link_targets(&self) -> impl Iterator<Item = &str> {
+        self.meta_info
             .iter()
-            .flatten()
-            .skip_while(|c| c.kind() != WordType::FootnoteData && c.content() != search)
-            .take_while(|c| c.kind() == WordType::Footnote)
+            .filter(|c| is_link_target(c))
             .map(Word::content)
-            .collect()
     }
 
     pub fn highlight_link(&self) -> Result<&str, String> {
         Ok(self
             .meta_info()
             .iter()
-            .filter(|c| matches!(c.kind(), WordType::LinkData | WordType::FootnoteInline))
+            .filter(|c| is_link_target(c))
             .nth(self.focused_index)
             .ok_or("index out of bounds")?
             .content())
@@ -342,10 +642,7 @@ impl TextComponent {
         if self.hidden {
             return 0;
         }
-        self.meta_info
-            .iter()
-            .filter(|c| matches!(c.kind(), WordType::LinkData | WordType::FootnoteInline))
-            .count()
+        self.meta_info.iter().filter(|c| is_link_target(c)).count()
     }
 
     #[must_use]
@@ -355,7 +652,7 @@ impl TextComponent {
             return heights;
         }
 
-        if let TextNode::Table(widths, row_heights) = self.kind() {
+        if let TextNode::Table(widths, row_heights, _) = self.kind() {
             let column_count = widths.len();
             let iter = self.content.chunks(column_count).enumerate();
 
@@ -387,6 +684,13 @@ impl TextComponent {
     }
 
     pub fn transform(&mut self, width: u16) {
+        // Nested content gets what is left next to its containers, but never
+        // so little that nothing fits
+        let width = if self.containers.is_empty() {
+            width
+        } else {
+            width.saturating_sub(self.containers_width()).max(10)
+        };
         match self.kind {
             TextNode::List => {
                 transform_list(self, width);
@@ -397,15 +701,23 @@ impl TextComponent {
             TextNode::Paragraph | TextNode::Task | TextNode::Quote => {
                 transform_paragraph(self, width);
             }
-            TextNode::LineBreak | TextNode::Heading | TextNode::DetailsSummary { .. } => {
+            TextNode::LineBreak
+            | TextNode::Heading
+            | TextNode::DetailsSummary { .. }
+            | TextNode::CalloutTitle(_)
+            | TextNode::EmbedTitle
+            | TextNode::EmbedEnd => {
                 self.height = 1;
             }
-            TextNode::Table(_, _) => {
+            TextNode::Table(..) => {
                 transform_table(self, width);
             }
+            TextNode::DefinitionList(_) => {
+                transform_definition_list(self, width);
+            }
             TextNode::HorizontalSeparator => self.height = 1,
             TextNode::Image => unreachable!("Image should not be transformed"),
-            TextNode::Footnote => self.height = 0,
+            TextNode::Footnote => transform_footnote(self, width),
         }
     }
 }
@@ -421,6 +733,13 @@ pub(crate) fn word_wrapping<'a>(
     let mut line = Vec::new();
     let mut line_len = 0;
     for word in words {
+        if word.kind() == WordType::HardBreak {
+            line.push(word.clone());
+            lines.push(std::mem::take(&mut line));
+            line_len = 0;
+            continue;
+        }
+        let word = &trim_line_start(word, line.is_empty() && !lines.is_empty());
         let word_len = display_width(word.content());
         if line_len + word_len <= width {
             line_len += word_len;
@@ -496,6 +815,37 @@ pub(crate) fn word_wrapping<'a>(
     lines
 }
 
+/// Words starting a line after a break lose the space before them
+fn trim_line_start(word: &Word, line_start: bool) -> Word {
+    let mut word = word.clone();
+    if line_start {
+        let content = word.content().trim_start().to_owned();
+        word.set_content(content);
+    }
+    word
+}
+
+/// Selectable words that are their own target, kept in the meta info next to
+/// the urls of links
+fn is_selectable_text(word: &Word) -> bool {
+    matches!(
+        word.kind(),
+        WordType::FootnoteInline | WordType::Abbreviation | WordType::Tag
+    )
+}
+
+/// What selecting a link or other selectable word leads to
+fn is_link_target(word: &Word) -> bool {
+    word.kind() == WordType::LinkData || is_selectable_text(word)
+}
+
+fn is_heading_anchor(word: &Word) -> bool {
+    matches!(
+        word.kind(),
+        WordType::MetaInfo(MetaData::HeadingId | MetaData::HeadingSlug)
+    )
+}
+
 fn display_width(text: &str) -> usize {
     UnicodeWidthStr::width(text)
 }
@@ -538,9 +888,7 @@ fn transform_paragraph(component: &mut TextComponent, width: u16) {
     let mut lines = word_wrapping(component.content.iter().flatten(), width, true);
 
     if component.kind() == TextNode::Quote {
-        let is_special_quote = !component.meta_info.is_empty();
-
-        for line in lines.iter_mut().skip(usize::from(is_special_quote)) {
+        for line in &mut lines {
             line.insert(0, Word::new(" ".to_string(), WordType::Normal));
         }
     }
@@ -549,6 +897,74 @@ fn transform_paragraph(component: &mut TextComponent, width: u16) {
     component.content = lines;
 }
 
+/// Footnotes are numbered, with the lines after the first indented past the
+/// number
+fn transform_footnote(component: &mut TextComponent, width: u16) {
+    let mut words = component.content.iter().flatten();
+    let Some(number) = words.next().cloned() else {
+        component.height = 0;
+        return;
+    };
+    let indent = display_width(number.content());
+    let width = (width as usize - 1).saturating_sub(indent).max(1);
+
+    let mut lines = word_wrapping(words, width, true);
+    for (i, line) in lines.iter_mut().enumerate() {
+        let prefix = if i == 0 {
+            number.clone()
+        } else {
+            Word::new(" ".repeat(indent), WordType::ListMarker)
+        };
+        line.insert(0, prefix);
+    }
+    if lines.is_empty() {
+        lines.push(vec![number]);
+    }
+
+    component.height = lines.len() as u16;
+    component.content = lines;
+}
+
+/// Definitions are indented under their term by this many columns
+const DEFINITION_INDENT: usize = 4;
+
+fn transform_definition_list(component: &mut TextComponent, width: u16) {
+    let TextNode::DefinitionList(terms) = &component.kind else {
+        unreachable!()
+    };
+    let width = (width as usize).saturating_sub(1).max(1);
+
+    let mut lines = Vec::new();
+    let mut line_terms = Vec::new();
+    for (row, &is_term) in component.content.iter().zip(terms) {
+        if is_term {
+            // Items are set apart by a blank line
+            if line_terms.last() == Some(&false) {
+                lines.push(Vec::new());
+                line_terms.push(false);
+            }
+            let wrapped = word_wrapping(row, width, true);
+            line_terms.extend(std::iter::repeat_n(true, wrapped.len()));
+            lines.extend(wrapped);
+        } else {
+            let mut wrapped =
+                word_wrapping(row, width.saturating_sub(DEFINITION_INDENT).max(1), true);
+            for line in &mut wrapped {
+                line.insert(
+                    0,
+                    Word::new(" ".repeat(DEFINITION_INDENT), WordType::Normal),
+                );
+            }
+            line_terms.extend(std::iter::repeat_n(false, wrapped.len()));
+            lines.extend(wrapped);
+        }
+    }
+
+    component.kind = TextNode::DefinitionList(line_terms);
+    component.height = lines.len() as u16;
+    component.content = lines;
+}
+
 fn transform_codeblock(component: &mut TextComponent) {
     let language = if let Some(word) = component.meta_info().first() {
         word.content()
@@ -568,6 +984,7 @@ fn transform_codeblock(component: &mut TextComponent) {
             vec![Word::new(String::new(), WordType::CodeBlock(Color::Reset))],
         );
     }
+    component.code = Some(content.clone());
     match highlight {
         HighlightInfo::Highlighted(e) => {
             let mut color = Color::Reset;
@@ -676,7 +1093,21 @@ fn transform_list(component: &mut TextComponent, width: u16) {
     let mut indent = 0;
     let mut extra_indent = 0;
     let mut tmp = indent;
+    let mut line_break = false;
     for word in component.content.iter_mut().flatten() {
+        if word.kind() == WordType::HardBreak {
+            line.push(word.clone());
+            lines.push(line);
+            let filler = " ".repeat(indent + 2 + extra_indent);
+            len = display_width(&filler);
+            line = vec![Word::new(filler, WordType::Normal)];
+            line_break = true;
+            continue;
+        }
+        if std::mem::take(&mut line_break) {
+            let content = word.content().trim_start().to_owned();
+            word.set_content(content);
+        }
         let word_len = display_width(word.content());
         if word_len + len < width as usize && word.kind() != WordType::ListMarker {
             len += word_len;
@@ -699,7 +1130,16 @@ fn transform_list(component: &mut TextComponent, width: u16) {
                             .last_mut()
                             .expect("List parse error. Stack is empty");
 
-                        *counter += 1;
+                        // The first item of a list decides where counting starts,
+                        // like in CommonMark
+                        *counter = if *counter == 0 {
+                            word.content()
+                                .trim_end_matches([' ', '.'])
+                                .parse()
+                                .unwrap_or(1)
+                        } else {
+                            *counter + 1
+                        };
 
                         word.set_content(format!("{counter}. "));
 
@@ -825,6 +1265,10 @@ fn transform_table(component: &mut TextComponent, width: u16) {
     // Subtract 1 to match the actual render area width (consistent with transform_paragraph)
     let width = width.saturating_sub(1);
     let content = &mut component.content;
+    let alignments = match &component.kind {
+        TextNode::Table(_, _, alignments) => alignments.clone(),
+        _ => Vec::new(),
+    };
 
     let column_count = component
         .meta_info
@@ -834,7 +1278,7 @@ fn transform_table(component: &mut TextComponent, width: u16) {
 
     if !content.len().is_multiple_of(column_count) || column_count == 0 {
         component.height = 1;
-        component.kind = TextNode::Table(vec![], vec![]);
+        component.kind = TextNode::Table(vec![], vec![], vec![]);
         return;
     }
 
@@ -870,9 +1314,25 @@ fn transform_table(component: &mut TextComponent, width: u16) {
     /////////////////////////////////////
     // Return if unbalanced width fits //
     /////////////////////////////////////
+    // Without wrapping only line breaks make a row taller
+    let unwrapped_heights: Vec<u16> = content
+        .chunks(column_count)
+        .map(|row| {
+            row.iter()
+                .map(|entry| {
+                    1 + entry
+                        .iter()
+                        .filter(|word| word.kind() == WordType::HardBreak)
+                        .count() as u16
+                })
+                .max()
+                .unwrap_or(1)
+        })
+        .collect();
+
     if width >= unbalanced_cells_width + styling_width {
-        component.height = row_count as u16 + 3;
-        component.kind = TextNode::Table(widths, vec![1; row_count]);
+        component.height = unwrapped_heights.iter().sum::<u16>() + 3;
+        component.kind = TextNode::Table(widths, unwrapped_heights, alignments);
         return;
     }
 
@@ -900,8 +1360,8 @@ fn transform_table(component: &mut TextComponent, width: u16) {
     };
 
     if overflowing_columns.is_empty() {
-        component.height = row_count as u16 + 3;
-        component.kind = TextNode::Table(widths, vec![1; row_count]);
+        component.height = unwrapped_heights.iter().sum::<u16>() + 3;
+        component.kind = TextNode::Table(widths, unwrapped_heights, alignments);
         return;
     }
 
@@ -963,7 +1423,7 @@ fn transform_table(component: &mut TextComponent, width: u16) {
 
     component.height = heights.iter().copied().sum::<u16>() + 3;
 
-    component.kind = TextNode::Table(widths_balanced, heights);
+    component.kind = TextNode::Table(widths_balanced, heights, alignments);
 }
 
 #[must_use]
@@ -9,32 +9,39 @@ pub enum MetaData {
     PLanguage,
     Other,
     ColumnsCount,
-    Important,
-    Note,
-    Tip,
-    Warning,
-    Caution,
     LineLength(u16),
     HeadingLevel(u8),
+    HeadingId,
+    HeadingSlug,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum WordType {
+    /// A use of an abbreviation, selectable to show what it stands for
+    Abbreviation,
     Bold,
     BoldItalic,
     Code,
     CodeBlock(Color),
-    Footnote,
+    Emoji,
     FootnoteData,
     FootnoteInline,
+    /// Ends the line, like `<br>`
+    HardBreak,
     Italic,
+    Kbd,
     Link,
     LinkData,
     ListMarker,
+    Mark,
     MetaInfo(MetaData),
     Normal,
     Selected,
     Strikethrough,
+    Subscript,
+    Superscript,
+    /// An Obsidian `#tag`, selectable to list the files with it
+    Tag,
     White,
 }
 
@@ -48,36 +55,54 @@ impl From<MdParseEnum> for WordType {
             | MdParseEnum::Indent
             | MdParseEnum::HorizontalSeparator => WordType::MetaInfo(MetaData::Other),
             MdParseEnum::FootnoteRef => WordType::FootnoteInline,
+            MdParseEnum::Abbreviation => WordType::Abbreviation,
             MdParseEnum::Code => WordType::Code,
             MdParseEnum::Bold => WordType::Bold,
             MdParseEnum::Italic => WordType::Italic,
             MdParseEnum::Strikethrough => WordType::Strikethrough,
+            MdParseEnum::HardBreak => WordType::HardBreak,
+            MdParseEnum::Kbd => WordType::Kbd,
+            MdParseEnum::Mark => WordType::Mark,
+            MdParseEnum::Emoji => WordType::Emoji,
+            MdParseEnum::Subscript => WordType::Subscript,
+            MdParseEnum::Superscript => WordType::Superscript,
+            MdParseEnum::Tag => WordType::Tag,
             MdParseEnum::Link | MdParseEnum::WikiLink | MdParseEnum::InlineLink => WordType::Link,
             MdParseEnum::BoldItalic => WordType::BoldItalic,
             MdParseEnum::Digit => WordType::ListMarker,
             MdParseEnum::Paragraph
             | MdParseEnum::AltText
             | MdParseEnum::Quote
+            // A `[!type]` line further down a quote is plain text
+            | MdParseEnum::Callout
             | MdParseEnum::Sentence
             | MdParseEnum::Word => WordType::Normal,
             MdParseEnum::LinkData => WordType::LinkData,
-            MdParseEnum::Imortant => WordType::MetaInfo(MetaData::Important),
-            MdParseEnum::Note => WordType::MetaInfo(MetaData::Note),
-            MdParseEnum::Tip => WordType::MetaInfo(MetaData::Tip),
-            MdParseEnum::Warning => WordType::MetaInfo(MetaData::Warning),
-            MdParseEnum::Caution => WordType::MetaInfo(MetaData::Caution),
+            MdParseEnum::HeadingId => WordType::MetaInfo(MetaData::HeadingId),
             MdParseEnum::Heading
+            | MdParseEnum::AbbreviationDefinition
+            | MdParseEnum::AbbreviationTitle
+            | MdParseEnum::Definition
+            | MdParseEnum::DefinitionList
+            | MdParseEnum::DefinitionTerm
             | MdParseEnum::BoldItalicStr
             | MdParseEnum::BoldStr
             | MdParseEnum::CodeBlock
             | MdParseEnum::CodeStr
             | MdParseEnum::Details
+            | MdParseEnum::Embed
             | MdParseEnum::DetailsBody
             | MdParseEnum::DetailsOpenAttr
             | MdParseEnum::DetailsSummary
+            | MdParseEnum::HtmlTag
             | MdParseEnum::Image
             | MdParseEnum::ItalicStr
+            | MdParseEnum::LinkReference
+            | MdParseEnum::LinkReferenceDefinition
+            | MdParseEnum::LinkTitle
             | MdParseEnum::ListContainer
+            | MdParseEnum::ListItemContainer
+            | MdParseEnum::QuoteContainer
             | MdParseEnum::OrderedList
             | MdParseEnum::StrikethroughStr
             | MdParseEnum::Footnote
@@ -26,12 +26,18 @@ enum MdFileComponent {
 pub struct MdFile {
     pub path: String,
     pub name: String,
+    /// Tags of the note, lowercase and without the `#`
+    pub tags: Vec<String>,
 }
 
 impl MdFile {
     #[must_use]
     pub fn new(path: String, name: String) -> Self {
-        Self { path, name }
+        Self {
+            path,
+            name,
+            tags: Vec::new(),
+        }
     }
 
     #[must_use]
@@ -80,6 +86,8 @@ pub struct FileTree {
     page: u32,
     list_state: ListState,
     search: Option<String>,
+    /// Only list the files with this tag
+    tag: Option<String>,
     loaded: bool,
 }
 
@@ -92,6 +100,7 @@ impl FileTree {
             list_state: ListState::default(),
             page: 0,
             search: None,
+            tag: None,
             loaded: false,
         }
     }
@@ -171,25 +180,34 @@ impl FileTree {
         self.state_mut().select(None);
         self.page = 0;
         self.search = query.map(ToOwned::to_owned);
-        match query {
-            Some(query) => {
-                self.files = find_files(&self.all_files, query)
-                    .into_iter()
-                    .map(MdFileComponent::File)
-                    .collect();
-            }
-            None => {
-                self.files = self
-                    .all_files
-                    .iter()
-                    .cloned()
-                    .map(MdFileComponent::File)
-                    .collect();
-            }
+        let files: Vec<MdFile> = self
+            .all_files
+            .iter()
+            .filter(|file| self.tag.as_ref().is_none_or(|tag| file.tags.contains(tag)))
+            .cloned()
+            .collect();
+        self.files = match query {
+            Some(query) => find_files(&files, query),
+            None => files,
         }
+        .into_iter()
+        .map(MdFileComponent::File)
+        .collect();
         self.fill_spacers();
     }
 
+    /// Only list the files with `tag`, or all files again
+    pub fn filter_tag(&mut self, tag: Option<&str>) {
+        self.tag = tag.map(str::to_lowercase);
+        let search = self.search.take();
+        self.search(search.as_deref());
+    }
+
+    #[must_use]
+    pub fn tag(&self) -> Option<&str> {
+        self.tag.as_deref()
+    }
+
     fn fill_spacers(&mut self) {
         let spacers = vec![MdFileComponent::Spacer; self.files.len()];
         self.files = self
@@ -292,8 +310,10 @@ impl FileTree {
 
     pub fn add_file(&mut self, file: MdFile) {
         self.all_files.push(file.clone());
-        self.files.push(MdFileComponent::File(file));
-        self.files.push(MdFileComponent::Spacer);
+        if self.tag.as_ref().is_none_or(|tag| file.tags.contains(tag)) {
+            self.files.push(MdFileComponent::File(file));
+            self.files.push(MdFileComponent::Spacer);
+        }
     }
 
     #[must_use]
@@ -364,10 +384,14 @@ impl Widget for FileTree {
 
         let y_height = items.len() / 2 * 3;
 
+        let title = match &self.tag {
+            Some(tag) => format!("MD-TUI #{tag}"),
+            None => "MD-TUI".to_owned(),
+        };
         let items = List::new(items)
             .block(
                 Block::default()
-                    .title("MD-TUI")
+                    .title(title)
                     .add_modifier(Modifier::BOLD)
                     .title_alignment(Alignment::Center),
             )
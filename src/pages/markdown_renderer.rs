@@ -1,24 +1,30 @@
 use std::borrow::Cow;
 use std::cmp;
+use std::num::NonZeroU16;
 
 use ratatui::{
-    buffer::Buffer,
+    buffer::{Buffer, CellDiffOption},
     layout::{Alignment, Rect},
     style::{Color, Modifier, Style, Stylize},
     text::{Line, Span},
     widgets::{Block, List, ListItem, Paragraph, Widget},
 };
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
 use crate::{
     nodes::{
+        callout::Callout,
+        root::ComponentRoot,
         textcomponent::{
-            TABLE_CELL_PADDING, TextComponent, TextNode, content_entry_len, word_wrapping,
+            Container, TABLE_CELL_PADDING, TextComponent, TextNode, content_entry_len,
+            word_wrapping,
         },
         word::{MetaData, Word, WordType},
     },
     util::{
         colors::{color_config, heading_colors},
         general::GENERAL_CONFIG,
+        hyperlink::{link_url, osc8, strip_osc8},
     },
 };
 
@@ -81,6 +87,7 @@ impl Widget for TextComponent {
             .unwrap_or_else(|| Word::new(String::new(), WordType::Normal));
 
         let area = Rect { height, y, ..area };
+        let area = render_containers(area, buf, self.containers());
 
         match kind {
             TextNode::Paragraph => render_paragraph(area, buf, self, clips),
@@ -88,21 +95,81 @@ impl Widget for TextComponent {
             TextNode::Task => render_task(area, buf, self, clips, &meta_info),
             TextNode::List => render_list(area, buf, self, clips),
             TextNode::CodeBlock => render_code_block(area, buf, self, clips),
-            TextNode::Table(widths, heights) => {
-                render_table(area, buf, self, clips, widths, heights);
+            TextNode::Table(widths, heights, alignments) => {
+                render_table(area, buf, self, clips, &widths, &heights, &alignments);
             }
             TextNode::Quote => render_quote(area, buf, self, clips),
+            TextNode::DefinitionList(terms) => {
+                render_definition_list(area, buf, self, clips, &terms);
+            }
             TextNode::LineBreak => (),
             TextNode::HorizontalSeparator => render_horizontal_separator(area, buf),
             TextNode::Image => todo!(),
-            TextNode::Footnote => (),
-            TextNode::DetailsSummary { folded, .. } => {
+            TextNode::Footnote => render_paragraph(area, buf, self, clips),
+            TextNode::DetailsSummary {
+                folded,
+                callout: None,
+                ..
+            } => {
                 render_details_summary(area, buf, self, folded);
             }
+            TextNode::DetailsSummary {
+                folded,
+                callout: Some(callout),
+                ..
+            } => render_callout_title(area, buf, &self, callout, Some(folded)),
+            TextNode::CalloutTitle(callout) => {
+                render_callout_title(area, buf, &self, callout, None);
+            }
+            TextNode::EmbedTitle | TextNode::EmbedEnd => render_embed_border(area, buf, &self),
         }
     }
 }
 
+/// Draw the indentation and quote bars of the list items and quotes a
+/// component is nested in. Returns the area left for the component.
+fn render_containers(area: Rect, buf: &mut Buffer, containers: &[Container]) -> Rect {
+    let right = area.x + cmp::min(area.width, GENERAL_CONFIG.width);
+    let mut x = area.x;
+    for container in containers {
+        if *container == Container::Embed {
+            let border = Span::styled(
+                "\u{2502}",
+                Style::default().fg(color_config().embed_border_color),
+            );
+            Paragraph::new(vec![Line::from(border); area.height as usize]).render(
+                Rect {
+                    x,
+                    width: right.saturating_sub(x),
+                    ..area
+                },
+                buf,
+            );
+        } else if let Container::Quote(callout) = container {
+            let bar_area = Rect {
+                x,
+                width: right.saturating_sub(x),
+                ..area
+            };
+            Block::default()
+                .style(Style::default().bg(color_config().quote_bg_color))
+                .render(bar_area, buf);
+            let bar = Span::styled("\u{2588}", Style::default().fg(callout_color(*callout)));
+            Paragraph::new(vec![Line::from(bar); area.height as usize]).render(bar_area, buf);
+        }
+        x += container.width();
+    }
+    Rect {
+        x,
+        width: area.width.saturating_sub(x - area.x),
+        ..area
+    }
+}
+
+fn callout_color(callout: Option<Callout>) -> Color {
+    callout.map_or(color_config().quote_default, Callout::color)
+}
+
 fn style_word_content<'a>(word: &Word, content: impl Into<Cow<'a, str>>) -> Span<'a> {
     match word.kind() {
         WordType::MetaInfo(_) | WordType::LinkData | WordType::FootnoteData => unreachable!(),
@@ -112,12 +179,39 @@ fn style_word_content<'a>(word: &Word, content: impl Into<Cow<'a, str>>) -> Span
                 .fg(color_config().link_selected_fg_color)
                 .bg(color_config().link_selected_bg_color),
         ),
-        WordType::Normal => Span::raw(content),
+        WordType::Normal | WordType::HardBreak | WordType::Emoji => Span::raw(content),
+        WordType::Subscript => {
+            Span::styled(content, Style::default().fg(color_config().subscript_color))
+        }
+        WordType::Superscript => Span::styled(
+            content,
+            Style::default().fg(color_config().superscript_color),
+        ),
+        WordType::Mark => Span::styled(
+            content,
+            Style::default()
+                .fg(color_config().mark_fg_color)
+                .bg(color_config().mark_bg_color),
+        ),
+        WordType::Kbd => Span::styled(
+            content,
+            Style::default()
+                .fg(color_config().kbd_fg_color)
+                .bg(color_config().kbd_bg_color)
+                .bold(),
+        ),
         WordType::Code => Span::styled(content, Style::default().fg(color_config().code_fg_color))
             .bg(color_config().code_bg_color),
         WordType::Link | WordType::FootnoteInline => {
             Span::styled(content, Style::default().fg(color_config().link_color))
         }
+        WordType::Tag => Span::styled(content, Style::default().fg(color_config().tag_color)),
+        WordType::Abbreviation => Span::styled(
+            content,
+            Style::default()
+                .fg(color_config().abbreviation_color)
+                .add_modifier(Modifier::UNDERLINED),
+        ),
         WordType::Italic => Span::styled(
             content,
             Style::default().fg(color_config().italic_color).italic(),
@@ -126,7 +220,7 @@ fn style_word_content<'a>(word: &Word, content: impl Into<Cow<'a, str>>) -> Span
             content,
             Style::default().fg(color_config().bold_color).bold(),
         ),
-        WordType::Strikethrough | WordType::Footnote => Span::styled(
+        WordType::Strikethrough => Span::styled(
             content,
             Style::default()
                 .fg(color_config().striketrough_color)
@@ -153,6 +247,80 @@ fn style_word_owned(word: &Word) -> Span<'static> {
     style_word_content(word, word.content().to_owned())
 }
 
+/// Make the links on screen terminal hyperlinks. The renderer decides where
+/// each word goes, so the words are looked up in the rendered lines and
+/// their cells wrapped in OSC 8 one by one.
+pub fn render_hyperlinks(buf: &mut Buffer, area: Rect, markdown: &ComponentRoot, scroll: u16) {
+    let mut from = (area.y, area.x);
+    for (y, word, target) in markdown.link_words() {
+        let text = word.content().trim();
+        if text.is_empty() || !(scroll..scroll.saturating_add(area.height)).contains(&y) {
+            continue;
+        }
+        let Some(url) = link_url(target) else {
+            continue;
+        };
+        let start = cmp::max(from, (area.y + y - scroll, area.x));
+        let Some((y, mut x)) = find_rendered(buf, area, text, start) else {
+            continue;
+        };
+        for c in text.chars() {
+            let width = c.width().unwrap_or(0).max(1) as u16;
+            let cell = &mut buf[(x, y)];
+            let symbol = osc8(&url, cell.symbol());
+            cell.set_symbol(&symbol)
+                .set_diff_option(CellDiffOption::ForcedWidth(
+                    NonZeroU16::new(width).unwrap_or(NonZeroU16::MIN),
+                ));
+            x += width;
+        }
+        from = (y, x);
+    }
+}
+
+/// The first `(y, x)` at or after `start` where `text` was rendered
+#[must_use]
+pub fn find_rendered(
+    buf: &Buffer,
+    area: Rect,
+    text: &str,
+    start: (u16, u16),
+) -> Option<(u16, u16)> {
+    let rendered_at = |mut x: u16, y: u16| {
+        text.chars().all(|c| {
+            let mut char_buf = [0; 4];
+            let found = x < area.right()
+                && strip_osc8(buf[(x, y)].symbol()) == c.encode_utf8(&mut char_buf);
+            x += c.width().unwrap_or(0).max(1) as u16;
+            found
+        })
+    };
+    (start.0..area.bottom()).find_map(|y| {
+        let from = if y == start.0 { start.1 } else { area.x };
+        (from..area.right())
+            .find(|x| rendered_at(*x, y))
+            .map(|x| (y, x))
+    })
+}
+
+/// A footnote styled like in the document, on a single line for the hover box
+#[must_use]
+pub fn footnote_line(component: &TextComponent) -> Line<'static> {
+    let mut spans = Vec::new();
+    for (i, line) in component.content().iter().enumerate() {
+        // Wrapping took the spaces between the lines
+        if i > 0 {
+            spans.push(Span::raw(" "));
+        }
+        spans.extend(
+            line.iter()
+                .filter(|w| w.kind() != WordType::ListMarker)
+                .map(style_word_owned),
+        );
+    }
+    Line::from(spans)
+}
+
 fn wrap_table_cell(entry: &[Word], width: u16) -> Vec<Vec<Word>> {
     if width == 0 {
         return vec![Vec::new()];
@@ -186,9 +354,16 @@ fn table_border_line(
 fn build_table_row_lines(
     row: &[Vec<Word>],
     widths: &[u16],
+    alignments: &[Alignment],
     row_height: u16,
     row_style: Option<Style>,
+    cursor_column: Option<usize>,
 ) -> Vec<Line<'static>> {
+    let cursor_style = Style::default()
+        .fg(color_config().link_selected_fg_color)
+        .bg(color_config().link_selected_bg_color)
+        .add_modifier(Modifier::BOLD);
+
     let wrapped_cells = row
         .iter()
         .zip(widths.iter())
@@ -200,20 +375,35 @@ fn build_table_row_lines(
             let mut spans = vec![Span::raw("│")];
 
             for (column_i, cell_lines) in wrapped_cells.iter().enumerate() {
+                let cell_start = spans.len();
                 spans.push(Span::raw(" ".repeat(TABLE_CELL_PADDING as usize)));
 
                 if let Some(words) = cell_lines.get(line_i) {
+                    // Every line of a wrapped cell is aligned on its own
+                    let padding =
+                        (widths[column_i] as usize).saturating_sub(content_entry_len(words));
+                    let left = match alignments.get(column_i) {
+                        Some(Alignment::Right) => padding,
+                        Some(Alignment::Center) => padding / 2,
+                        _ => 0,
+                    };
+                    if left > 0 {
+                        spans.push(Span::raw(" ".repeat(left)));
+                    }
                     spans.extend(words.iter().map(style_word_owned));
-
-                    let padding = widths[column_i] as usize - content_entry_len(words);
-                    if padding > 0 {
-                        spans.push(Span::raw(" ".repeat(padding)));
+                    if padding > left {
+                        spans.push(Span::raw(" ".repeat(padding - left)));
                     }
                 } else {
                     spans.push(Span::raw(" ".repeat(widths[column_i] as usize)));
                 }
 
                 spans.push(Span::raw(" ".repeat(TABLE_CELL_PADDING as usize)));
+                if cursor_column == Some(column_i) {
+                    for span in &mut spans[cell_start..] {
+                        *span = span.clone().patch_style(cursor_style);
+                    }
+                }
                 spans.push(Span::raw("│"));
             }
 
@@ -227,21 +417,36 @@ fn build_table_row_lines(
         .collect()
 }
 
-fn build_table_lines(content: &[Vec<Word>], widths: &[u16], heights: &[u16]) -> Vec<Line<'static>> {
+fn build_table_lines(
+    content: &[Vec<Word>],
+    widths: &[u16],
+    heights: &[u16],
+    alignments: &[Alignment],
+    cursor: Option<(usize, usize)>,
+) -> Vec<Line<'static>> {
     let column_count = widths.len();
     let header_style = Style::default()
         .fg(color_config().table_header_fg_color)
         .bg(color_config().table_header_bg_color);
+    let cursor_row_style = Style::default().bg(color_config().table_cursor_bg_color);
 
     let mut lines = vec![table_border_line(widths, "╭", "┬", "╮")];
 
     for (row_i, row) in content.chunks(column_count).enumerate() {
-        let row_style = (row_i == 0).then_some(header_style);
+        let is_cursor_row = cursor.is_some_and(|(row, _)| row == row_i);
+        let row_style = match (row_i == 0, is_cursor_row) {
+            (true, true) => Some(header_style.patch(cursor_row_style)),
+            (true, false) => Some(header_style),
+            (false, true) => Some(cursor_row_style),
+            (false, false) => None,
+        };
         lines.extend(build_table_row_lines(
             row,
             widths,
+            alignments,
             heights[row_i],
             row_style,
+            cursor.filter(|_| is_cursor_row).map(|(_, column)| column),
         ));
 
         if row_i == 0 {
@@ -258,8 +463,6 @@ fn render_quote(area: Rect, buf: &mut Buffer, component: TextComponent, clip: Cl
         .scroll_offset()
         .saturating_sub(component.y_offset());
 
-    let meta = component.meta_info().to_owned();
-
     let mut content = component.content_owned();
     let content = match clip {
         Clipping::Both => {
@@ -288,23 +491,7 @@ fn render_quote(area: Rect, buf: &mut Buffer, component: TextComponent, clip: Cl
         .map(|c| Line::from(c.iter().map(style_word).collect::<Vec<_>>()))
         .collect::<Vec<_>>();
 
-    let bar_color = if let Some(meta) = meta.first() {
-        meta.content()
-            .split_whitespace()
-            .next()
-            .map(str::to_lowercase)
-            .map_or(color_config().quote_bg_color, |c| match c.as_str() {
-                "[!tip]" => color_config().quote_tip,
-                "[!warning]" => color_config().quote_warning,
-                "[!caution]" => color_config().quote_caution,
-                "[!important]" => color_config().quote_important,
-                "[!note]" => color_config().quote_note,
-                _ => color_config().quote_default,
-            })
-    } else {
-        Color::White
-    };
-    let vertical_marker = Span::styled("\u{2588}", Style::default().fg(bar_color));
+    let vertical_marker = Span::styled("\u{2588}", Style::default().fg(callout_color(None)));
 
     let marker_paragraph = Paragraph::new(vec![Line::from(vertical_marker); content.len()])
         .bg(color_config().quote_bg_color);
@@ -368,12 +555,19 @@ fn render_heading(area: Rect, buf: &mut Buffer, component: TextComponent) {
         1
     };
 
-    let content: Vec<Span<'_>> = component
+    let mut content: Vec<Span<'_>> = component
         .content()
         .iter()
         .flatten()
         .map(|c| style_heading(c, indent))
         .collect();
+    if let Some(lines) = component.folded_lines() {
+        let unit = if lines == 1 { "line" } else { "lines" };
+        content.push(Span::styled(
+            format!(" \u{2026} {lines} {unit}"),
+            Style::default().fg(color_config().fold_marker_color),
+        ));
+    }
 
     let paragraph = match indent {
         1 => Paragraph::new(Line::from(content))
@@ -422,6 +616,54 @@ fn render_paragraph(area: Rect, buf: &mut Buffer, component: TextComponent, clip
     paragraph.render(area, buf);
 }
 
+fn render_definition_list(
+    area: Rect,
+    buf: &mut Buffer,
+    component: TextComponent,
+    clip: Clipping,
+    terms: &[bool],
+) {
+    let top = component
+        .scroll_offset()
+        .saturating_sub(component.y_offset());
+    let mut content: Vec<_> = component.content_owned().into_iter().zip(terms).collect();
+    match clip {
+        Clipping::Both => {
+            content.drain(0..top as usize);
+            content.drain(area.height as usize..);
+        }
+        Clipping::Upper => {
+            let offset = content.len() - area.height as usize;
+            content.drain(0..offset);
+        }
+        Clipping::Lower => {
+            content.drain(area.height as usize..);
+        }
+        Clipping::None => (),
+    }
+
+    let term_style = Style::default()
+        .fg(color_config().definition_term_color)
+        .add_modifier(Modifier::BOLD);
+    let lines = content
+        .iter()
+        .map(|(line, is_term)| {
+            let spans = line.iter().map(|word| {
+                let span = style_word(word);
+                // Links and code in a term keep their colors
+                match word.kind() {
+                    WordType::Normal if **is_term => span.patch_style(term_style),
+                    _ if **is_term => span.add_modifier(Modifier::BOLD),
+                    _ => span,
+                }
+            });
+            Line::from(spans.collect::<Vec<_>>())
+        })
+        .collect::<Vec<_>>();
+
+    Paragraph::new(lines).render(area, buf);
+}
+
 fn render_details_summary(area: Rect, buf: &mut Buffer, component: TextComponent, folded: bool) {
     let focused = component.is_focused();
     let mut style = Style::default().add_modifier(Modifier::BOLD);
@@ -438,6 +680,65 @@ fn render_details_summary(area: Rect, buf: &mut Buffer, component: TextComponent
     Paragraph::new(Line::from(spans)).render(area, buf);
 }
 
+/// The top border of an embedded note, with its name, or the bottom border
+fn render_embed_border(area: Rect, buf: &mut Buffer, component: &TextComponent) {
+    let style = Style::default().fg(color_config().embed_border_color);
+    let width = cmp::min(area.width, GENERAL_CONFIG.width) as usize;
+    let mut spans = Vec::new();
+    let mut used = 1;
+    if component.kind() == TextNode::EmbedTitle {
+        spans.push(Span::styled("\u{256d}\u{2500} ", style));
+        for word in component.content().iter().flatten() {
+            used += word.content().width();
+            spans.push(Span::styled(
+                word.content().to_owned(),
+                style.add_modifier(Modifier::BOLD),
+            ));
+        }
+        spans.push(Span::styled(" ", style));
+        used += 3;
+    } else {
+        spans.push(Span::styled("\u{2570}", style));
+    }
+    spans.push(Span::styled(
+        "\u{2500}".repeat(width.saturating_sub(used)),
+        style,
+    ));
+    Paragraph::new(Line::from(spans)).render(area, buf);
+}
+
+/// The title of a callout, with its icon and, if it can be folded, a fold
+/// marker. Focused like a `<details>` summary when selected.
+fn render_callout_title(
+    area: Rect,
+    buf: &mut Buffer,
+    component: &TextComponent,
+    callout: Callout,
+    folded: Option<bool>,
+) {
+    let mut style = Style::default()
+        .fg(callout.color())
+        .add_modifier(Modifier::BOLD);
+    if component.is_focused() {
+        style = style
+            .fg(color_config().link_selected_fg_color)
+            .bg(color_config().link_selected_bg_color);
+    }
+    let mut spans = Vec::new();
+    if !callout.icon().is_empty() {
+        spans.push(Span::styled(format!("{} ", callout.icon()), style));
+    }
+    for word in component.content().iter().flatten() {
+        spans.push(Span::styled(word.content().to_owned(), style));
+    }
+    match folded {
+        Some(true) => spans.push(Span::styled(" ▶", style)),
+        Some(false) => spans.push(Span::styled(" ▼", style)),
+        None => {}
+    }
+    Paragraph::new(Line::from(spans)).render(area, buf);
+}
+
 fn render_list(area: Rect, buf: &mut Buffer, component: TextComponent, clip: Clipping) {
     let top = component
         .scroll_offset()
@@ -549,8 +850,9 @@ fn render_table(
     buf: &mut Buffer,
     component: TextComponent,
     clip: Clipping,
-    widths: Vec<u16>,
-    heights: Vec<u16>,
+    widths: &[u16],
+    heights: &[u16],
+    alignments: &[Alignment],
 ) {
     let column_count = widths.len();
 
@@ -563,7 +865,8 @@ fn render_table(
         .scroll_offset()
         .saturating_sub(component.y_offset());
 
-    let mut lines = build_table_lines(component.content(), &widths, &heights);
+    let cursor = component.table_view().map(|view| (view.row, view.column));
+    let mut lines = build_table_lines(component.content(), widths, heights, alignments, cursor);
 
     let lines = match clip {
         Clipping::Both => {
@@ -1,2 +1,3 @@
 pub mod file_explorer;
 pub mod markdown_renderer;
+pub mod tag_browser;
@@ -0,0 +1,175 @@
+use std::collections::BTreeMap;
+
+use ratatui::buffer::Buffer;
+use ratatui::layout::{Alignment, Rect};
+use ratatui::style::Stylize;
+use ratatui::text::{Line, Text};
+use ratatui::widgets::{HighlightSpacing, Widget};
+use ratatui::{
+    style::{Modifier, Style},
+    widgets::{Block, List, ListItem, ListState, StatefulWidget},
+};
+
+use crate::pages::file_explorer::MdFile;
+use crate::util::colors::color_config;
+
+/// A tag and how many files have it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tag {
+    pub name: String,
+    pub count: usize,
+}
+
+impl From<Tag> for ListItem<'_> {
+    fn from(val: Tag) -> Self {
+        let files = if val.count == 1 { "file" } else { "files" };
+        ListItem::new(Line::from(vec![
+            format!("#{}", val.name).fg(color_config().tag_color),
+            format!("  {} {files}", val.count)
+                .italic()
+                .fg(color_config().file_tree_path_color),
+        ]))
+    }
+}
+
+/// Every tag of the files in the file tree. Selecting one lists the files
+/// with it.
+#[derive(Debug, Clone, Default)]
+pub struct TagBrowser {
+    tags: Vec<Tag>,
+    list_state: ListState,
+}
+
+impl TagBrowser {
+    #[must_use]
+    pub fn new(files: &[MdFile]) -> Self {
+        let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+        for tag in files.iter().flat_map(|file| &file.tags) {
+            *counts.entry(tag).or_default() += 1;
+        }
+        let tags = counts
+            .into_iter()
+            .map(|(name, count)| Tag {
+                name: name.to_owned(),
+                count,
+            })
+            .collect();
+
+        Self {
+            tags,
+            list_state: ListState::default().with_selected(Some(0)),
+        }
+    }
+
+    #[must_use]
+    pub fn tags(&self) -> &[Tag] {
+        &self.tags
+    }
+
+    #[must_use]
+    pub fn selected(&self) -> Option<&Tag> {
+        self.tags.get(self.list_state.selected()?)
+    }
+
+    pub fn next(&mut self) {
+        let i = match self.list_state.selected() {
+            Some(i) if i + 1 < self.tags.len() => i + 1,
+            _ => 0,
+        };
+        self.list_state.select(Some(i));
+    }
+
+    pub fn previous(&mut self) {
+        let i = match self.list_state.selected() {
+            Some(0) | None => self.tags.len().saturating_sub(1),
+            Some(i) => i - 1,
+        };
+        self.list_state.select(Some(i));
+    }
+
+    pub fn next_page(&mut self, height: u16) {
+        let i = self.list_state.selected().unwrap_or(0) + usize::from(height / 2);
+        self.list_state
+            .select(Some(i.min(self.tags.len().saturating_sub(1))));
+    }
+
+    pub fn previous_page(&mut self, height: u16) {
+        let i = self.list_state.selected().unwrap_or(0);
+        self.list_state
+            .select(Some(i.saturating_sub(usize::from(height / 2))));
+    }
+
+    pub fn first(&mut self) {
+        self.list_state.select(Some(0));
+    }
+
+    pub fn last(&mut self) {
+        self.list_state
+            .select(Some(self.tags.len().saturating_sub(1)));
+    }
+}
+
+impl Widget for TagBrowser {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let block = Block::default()
+            .title("Tags")
+            .add_modifier(Modifier::BOLD)
+            .title_alignment(Alignment::Center);
+
+        if self.tags.is_empty() {
+            let inner = block.inner(area);
+            block.render(area, buf);
+            Text::from("No tags found")
+                .fg(color_config().file_tree_path_color)
+                .render(inner, buf);
+            return;
+        }
+
+        let mut state = self.list_state;
+        let items = List::new(self.tags)
+            .block(block)
+            .highlight_style(
+                Style::default()
+                    .fg(color_config().file_tree_selected_fg_color)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .highlight_symbol("\u{02503} ")
+            .highlight_spacing(HighlightSpacing::Always);
+
+        StatefulWidget::render(items, area, buf, &mut state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tags_are_counted_per_file() {
+        let mut files = vec![
+            MdFile::new("./a.md".to_owned(), "a.md".to_owned()),
+            MdFile::new("./b.md".to_owned(), "b.md".to_owned()),
+        ];
+        files[0].tags = vec!["rust".to_owned(), "tui".to_owned()];
+        files[1].tags = vec!["rust".to_owned()];
+
+        let mut browser = TagBrowser::new(&files);
+        assert_eq!(
+            browser.tags(),
+            [
+                Tag {
+                    name: "rust".to_owned(),
+                    count: 2
+                },
+                Tag {
+                    name: "tui".to_owned(),
+                    count: 1
+                },
+            ]
+        );
+        browser.previous();
+        assert_eq!(browser.selected().map(|t| t.name.as_str()), Some("tui"));
+        browser.next();
+        assert_eq!(browser.selected().map(|t| t.name.as_str()), Some("rust"));
+    }
+}
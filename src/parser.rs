@@ -1,21 +1,49 @@
-use std::sync::atomic::{AtomicU32, Ordering};
+use std::{
+    collections::HashMap,
+    fmt::{self, Display},
+    num::NonZeroUsize,
+    str::FromStr,
+    sync::{
+        Mutex, PoisonError, RwLock,
+        atomic::{AtomicU32, Ordering},
+    },
+};
 
 use image::ImageReader;
 use itertools::Itertools;
 use pest::{
-    Parser,
+    Parser, Position,
+    error::LineColLocation,
     iterators::{Pair, Pairs},
 };
 use pest_derive::Parser;
-use ratatui::style::Color;
-
-use crate::nodes::{
-    image::ImageComponent,
-    root::{Component, ComponentRoot},
-    textcomponent::{TextComponent, TextNode},
-    word::{MetaData, Word, WordType},
+use ratatui::{layout::Alignment, style::Color};
+
+use crate::{
+    nodes::{
+        image::ImageComponent,
+        root::{Component, ComponentRoot},
+        textcomponent::{Container, TextComponent, TextNode},
+        word::{MetaData, Word, WordType},
+    },
+    search::heading_slug,
 };
 
+mod abbreviations;
+mod callouts;
+#[cfg(feature = "commonmark")]
+mod commonmark;
+mod containers;
+mod embeds;
+mod entities;
+mod escapes;
+mod extensions;
+mod footnotes;
+mod html;
+mod references;
+
+use containers::Segment;
+
 /// Process-wide monotonic counter for assigning unique IDs to `<details>`
 /// blocks. Each parsed details summary gets a fresh ID so it can be addressed
 /// by the runtime fold-toggle and selector independently of its position in
@@ -42,17 +70,93 @@ fn tag_owning_details(components: &mut [Component], id: u32) {
 #[grammar = "md.pest"]
 pub struct MdParser;
 
+/// A region of the document the grammar could not parse. Line and column are
+/// 1-based and relative to the whole document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseDiagnostic {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl Display for ParseDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}: {}", self.line, self.column, self.message)
+    }
+}
+
+/// The frontend used to turn markdown text into a parse tree. Both produce
+/// the same component tree, so rendering doesn't care which one was used.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ParserBackend {
+    /// The pest grammar in `md.pest`
+    #[default]
+    Pest,
+    /// A CommonMark + GFM conformant parser. Only available when built with
+    /// the `commonmark` feature, otherwise the pest grammar is used.
+    CommonMark,
+}
+
+impl FromStr for ParserBackend {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "pest" => Ok(Self::Pest),
+            "commonmark" | "cmark" | "gfm" => Ok(Self::CommonMark),
+            _ => Err(format!(
+                "Unknown parser `{s}`, expected `pest` or `commonmark`"
+            )),
+        }
+    }
+}
+
+impl ParserBackend {
+    /// Whether this backend is compiled in.
+    #[must_use]
+    pub fn is_available(self) -> bool {
+        match self {
+            Self::Pest => true,
+            Self::CommonMark => cfg!(feature = "commonmark"),
+        }
+    }
+}
+
+static PARSER_BACKEND: RwLock<ParserBackend> = RwLock::new(ParserBackend::Pest);
+
+pub fn set_parser_backend(backend: ParserBackend) {
+    *PARSER_BACKEND.write().unwrap() = backend;
+}
+
+#[must_use]
+pub fn parser_backend() -> ParserBackend {
+    *PARSER_BACKEND.read().unwrap()
+}
+
 pub fn parse_markdown(name: Option<&str>, content: &str, width: u16) -> ComponentRoot {
-    let root: Pairs<'_, Rule> = if let Ok(file) = MdParser::parse(Rule::txt, content) {
-        file
-    } else {
-        return ComponentRoot::new(name.map(str::to_string), Vec::new());
-    };
+    parse_markdown_with(parser_backend(), name, content, width)
+}
 
-    let root_pair = root.into_iter().next().unwrap();
+pub fn parse_markdown_with(
+    backend: ParserBackend,
+    name: Option<&str>,
+    content: &str,
+    width: u16,
+) -> ComponentRoot {
+    let mut root = build_root(backend, name, content);
+    root.transform(width);
+    root.recompute_visibility();
+    root
+}
 
-    let children = parse_text(root_pair)
-        .children_owned()
+/// Parse a document into components that are not laid out yet
+fn build_root(backend: ParserBackend, name: Option<&str>, content: &str) -> ComponentRoot {
+    let parsed = build_nodes(backend, name, content);
+    // Numbered once the embedded notes are in, so they share the numbers and
+    // the Footnotes section
+    let nodes = footnotes::resolve(parsed.nodes);
+
+    let children = nodes
         .into_iter()
         .dedup_by(|x, y| {
             x.kind() == MdParseEnum::BlockSeparator && y.kind == MdParseEnum::BlockSeparator
@@ -62,20 +166,340 @@ pub fn parse_markdown(name: Option<&str>, content: &str, width: u16) -> Componen
     let parse_root = ParseRoot::new(name.map(str::to_string), children);
 
     let mut root = node_to_component(parse_root).add_missing_components();
-
-    root.transform(width);
-    root.recompute_visibility();
+    root.assign_heading_slugs();
+    root.set_parse_errors(parsed.errors);
+    root.set_link_titles(parsed.link_titles);
+    root.set_abbreviations(parsed.abbreviations);
     root
 }
 
+/// A document as nodes, with the notes it embeds put in
+struct ParsedNodes {
+    nodes: Vec<ParseNode>,
+    errors: Vec<ParseDiagnostic>,
+    link_titles: HashMap<String, String>,
+    abbreviations: HashMap<String, String>,
+}
+
+/// Parse a document into nodes and put the notes it embeds into them, which
+/// are parsed the same way
+fn build_nodes(backend: ParserBackend, name: Option<&str>, content: &str) -> ParsedNodes {
+    let (nodes, errors) = match backend {
+        #[cfg(feature = "commonmark")]
+        ParserBackend::CommonMark => (commonmark::parse_nodes(content), Vec::new()),
+        _ => parse_nodes(content),
+    };
+    let (nodes, link_titles) = references::resolve(nodes);
+    let nodes = html::resolve(nodes);
+    let nodes = extensions::resolve(nodes);
+    let (nodes, abbreviations) = abbreviations::resolve(nodes);
+
+    let (nodes, embedded_link_titles, embedded_abbreviations) =
+        embeds::within(backend, name, || embeds::expand(nodes));
+    ParsedNodes {
+        nodes,
+        errors,
+        link_titles: merge(link_titles, embedded_link_titles),
+        abbreviations: merge(abbreviations, embedded_abbreviations),
+    }
+}
+
+/// Definitions of the document itself win over the ones of notes it embeds
+fn merge(
+    own: HashMap<String, String>,
+    embedded: HashMap<String, String>,
+) -> HashMap<String, String> {
+    let mut merged = embedded;
+    merged.extend(own);
+    merged
+}
+
+/// Returns every error of the pest grammar in `content`, in document order,
+/// whichever backend is chosen. Used by `mdt --diagnose` to produce output
+/// for bug reports.
+#[must_use]
+pub fn diagnose(content: &str) -> Vec<ParseDiagnostic> {
+    parse_nodes(content).1
+}
+
+/// Parse the whole document in one go. If the grammar rejects it, or stops
+/// before the end, fall back to parsing it block by block so that a single
+/// odd construct doesn't take the rest of the document with it.
+fn parse_nodes(content: &str) -> (Vec<ParseNode>, Vec<ParseDiagnostic>) {
+    if content.trim().is_empty() {
+        return (Vec::new(), Vec::new());
+    }
+
+    let segments = containers::split(content);
+    if let [Segment::Text { .. }] = segments.as_slice() {
+        return parse_flat(content);
+    }
+
+    let mut nodes = Vec::new();
+    let mut errors = Vec::new();
+    for segment in segments {
+        let (line, segment_errors) = match segment {
+            Segment::Text { line, text } => {
+                let (children, errors) = parse_flat(&text);
+                nodes.extend(children);
+                (line, errors)
+            }
+            Segment::Quote {
+                line,
+                callout,
+                text,
+            } => {
+                let (children, errors) = parse_nodes(&text);
+                let mut node =
+                    ParseNode::new(MdParseEnum::QuoteContainer, callout.unwrap_or_default());
+                node.add_children(trim_block_separators(children));
+                nodes.push(node);
+                (line, errors)
+            }
+            Segment::ListItem { line, indent, text } => {
+                let (children, errors) = parse_nodes(&text);
+                let mut node = ParseNode::new(MdParseEnum::ListItemContainer, " ".repeat(indent));
+                node.add_children(trim_block_separators(children));
+                nodes.push(node);
+                (line, errors)
+            }
+        };
+        errors.extend(segment_errors.into_iter().map(|mut e| {
+            e.line += line;
+            e
+        }));
+    }
+    (nodes, errors)
+}
+
+/// Parse content without list items or quotes that contain other blocks
+fn parse_flat(content: &str) -> (Vec<ParseNode>, Vec<ParseDiagnostic>) {
+    if content.trim().is_empty() {
+        return (Vec::new(), Vec::new());
+    }
+
+    match parse_block(content) {
+        Ok(nodes) => (nodes, Vec::new()),
+        Err(_) => recover_blocks(content, parse_block),
+    }
+}
+
+/// Drop the blank lines around the content of a container and merge runs of
+/// them, the container is already separated from its surroundings
+fn trim_block_separators(nodes: Vec<ParseNode>) -> Vec<ParseNode> {
+    let is_separator = |node: &ParseNode| node.kind() == MdParseEnum::BlockSeparator;
+    let mut nodes: Vec<ParseNode> = nodes
+        .into_iter()
+        .skip_while(is_separator)
+        .dedup_by(|a, b| is_separator(a) && is_separator(b))
+        .collect();
+    while nodes.last().is_some_and(is_separator) {
+        nodes.pop();
+    }
+    nodes
+}
+
+/// Rule calls a parse may make for each block of its input, at least
+/// `MIN_CALLS` in all. A block may take `CALLS_PER_BYTE` calls for each of its
+/// bytes, or the square of its length divided by `CALLS_DIVISOR` if that is
+/// more. The grammar backtracks more the longer a block is. Long real lists
+/// take about n² / 25 calls, but some input, like a long run of unclosed `**`,
+/// takes more than ten times n² and would hang the viewer for minutes. Parses
+/// past the limit fail and are shown as plain text instead.
+const MIN_CALLS: usize = 10_000_000;
+const CALLS_PER_BYTE: usize = 1_000;
+const CALLS_DIVISOR: usize = 4;
+
+/// Held while parsing. Pest reads the call limit from a global when a parse
+/// starts, so parses on other threads, like those of tests, take turns.
+static PARSE_LOCK: Mutex<()> = Mutex::new(());
+
+fn call_limit(content: &str) -> usize {
+    split_blocks(content)
+        .into_iter()
+        .map(|(_, block)| {
+            let len = block.len();
+            (len.saturating_mul(len) / CALLS_DIVISOR).max(len.saturating_mul(CALLS_PER_BYTE))
+        })
+        .fold(MIN_CALLS, usize::saturating_add)
+}
+
+fn parse_block(block: &str) -> Result<Vec<ParseNode>, ParseDiagnostic> {
+    let limit = call_limit(block);
+    let guard = PARSE_LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+    pest::set_call_limit(NonZeroUsize::new(limit));
+    let root = MdParser::parse(Rule::txt, block).map_err(|e| {
+        let (line, column) = match e.line_col {
+            LineColLocation::Pos(pos) | LineColLocation::Span(pos, _) => pos,
+        };
+        ParseDiagnostic {
+            line,
+            column,
+            message: e.variant.message().into_owned(),
+        }
+    })?;
+    drop(guard);
+
+    let root_pair = root.into_iter().next().unwrap();
+    let end = root_pair.as_span().end();
+    if !block[end..].trim().is_empty() {
+        let (line, column) =
+            Position::new(block, end).map_or((1, 1), |position| position.line_col());
+        return Err(ParseDiagnostic {
+            line,
+            column,
+            message: "unexpected input".to_string(),
+        });
+    }
+
+    Ok(parse_text(root_pair).children_owned())
+}
+
+/// Parse each block of `content` on its own. Blocks that still fail are kept
+/// as plain preformatted text, and their errors are returned with positions
+/// relative to `content`.
+fn recover_blocks(
+    content: &str,
+    parse: impl Fn(&str) -> Result<Vec<ParseNode>, ParseDiagnostic>,
+) -> (Vec<ParseNode>, Vec<ParseDiagnostic>) {
+    let mut nodes = Vec::new();
+    let mut errors = Vec::new();
+
+    for (line_offset, block) in split_blocks(content) {
+        if !nodes.is_empty() {
+            nodes.push(ParseNode::new(MdParseEnum::BlockSeparator, String::new()));
+        }
+        match parse(block) {
+            Ok(block_nodes) => nodes.extend(block_nodes),
+            Err(error) => {
+                errors.push(ParseDiagnostic {
+                    line: error.line + line_offset,
+                    ..error
+                });
+                nodes.push(preformatted_node(block));
+            }
+        }
+    }
+
+    (nodes, errors)
+}
+
+/// Split `content` on blank lines, keeping fenced code blocks in one piece.
+/// Returns each block together with the number of lines before it.
+fn split_blocks(content: &str) -> Vec<(usize, &str)> {
+    let mut blocks = Vec::new();
+    let mut start: Option<(usize, usize)> = None;
+    let mut fence: Option<&str> = None;
+    let mut offset = 0;
+
+    for (line_i, line) in content.split_inclusive('\n').enumerate() {
+        let trimmed = line.trim();
+
+        if let Some(marker) = fence {
+            if trimmed.starts_with(marker) {
+                fence = None;
+            }
+        } else if let Some(marker) = ["```", "~~~"].into_iter().find(|m| trimmed.starts_with(m)) {
+            fence = Some(marker);
+        }
+
+        if trimmed.is_empty() && fence.is_none() {
+            if let Some((line_start, byte_start)) = start.take() {
+                blocks.push((line_start, content[byte_start..offset].trim_end()));
+            }
+        } else if start.is_none() {
+            start = Some((line_i, offset));
+        }
+
+        offset += line.len();
+    }
+
+    if let Some((line_start, byte_start)) = start {
+        blocks.push((line_start, content[byte_start..].trim_end()));
+    }
+
+    blocks
+}
+
+/// Show `block` verbatim, the same way as a code block without a language.
+fn preformatted_node(block: &str) -> ParseNode {
+    let mut node = ParseNode::new(MdParseEnum::CodeBlock, block.to_string());
+    node.add_children(
+        block
+            .lines()
+            .map(|line| {
+                ParseNode::new(
+                    MdParseEnum::CodeBlockStr,
+                    format!("\n{}", line.replace('\t', "    ")),
+                )
+            })
+            .collect(),
+    );
+    node
+}
+
 fn parse_text(pair: Pair<'_, Rule>) -> ParseNode {
-    let content = if pair.as_rule() == Rule::code_line {
-        pair.as_str().replace('\t', "    ").replace('\r', "")
-    } else {
-        pair.as_str().replace('\n', " ")
+    let content = match pair.as_rule() {
+        Rule::code_line => pair.as_str().replace('\t', "    ").replace('\r', ""),
+        // Lines of indented code start with a newline like fenced ones,
+        // without the indent
+        Rule::indented_code_line | Rule::indented_code_newline => {
+            let line = pair.as_str().replace('\r', "");
+            let line = line.trim_start_matches('\n');
+            let line = line
+                .strip_prefix("    ")
+                .or_else(|| line.strip_prefix('\t'))
+                .unwrap_or(line);
+            format!("\n{}", line.replace('\t', "    "))
+        }
+        Rule::hard_break => pair.as_str().trim().to_owned(),
+        // A reference is its label, with the space in front of it
+        Rule::footnote_ref_container => {
+            let space = if pair.as_str().starts_with([' ', '\t']) {
+                " "
+            } else {
+                ""
+            };
+            let label = pair.into_inner().next().map_or("", |label| label.as_str());
+            return ParseNode::new(MdParseEnum::FootnoteRef, format!("{space}{label}"));
+        }
+        Rule::word
+        | Rule::h_word
+        | Rule::t_word
+        | Rule::setext_h_word
+        | Rule::bold_word
+        | Rule::bold_italic_word
+        | Rule::italic_word_var_1
+        | Rule::italic_word_var_2
+        | Rule::strikethrough_word
+        | Rule::link_word
+        | Rule::wiki_link_word
+        | Rule::link_data
+        | Rule::link_ref_url
+        | Rule::link_title_double
+        | Rule::link_title_single
+        | Rule::link_title_paren
+        | Rule::alt_text => escapes::unescape(&pair.as_str().replace('\n', " ")),
+        _ => pair.as_str().replace('\n', " "),
     };
     let mut component = ParseNode::new(pair.as_rule().into(), content);
-    let children = parse_node_children(pair.into_inner());
+    let mut children = parse_node_children(pair.into_inner());
+    // A hard break ending a block, before a blank line or another block,
+    // doesn't break anything. A backslash there is just a backslash.
+    if let Some(last) = children.last_mut()
+        && last.kind() == MdParseEnum::HardBreak
+    {
+        if last.content().is_empty() {
+            children.pop();
+        } else {
+            *last = ParseNode::new(MdParseEnum::Word, last.content().to_owned());
+        }
+    }
+    for child in &mut children {
+        if child.kind() == MdParseEnum::HardBreak {
+            child.content.clear();
+        }
+    }
     component.add_children(children);
     component
 }
@@ -99,10 +523,40 @@ fn node_to_component(root: ParseRoot) -> ComponentRoot {
 }
 
 fn parse_components(parse_node: ParseNode) -> Vec<Component> {
-    if parse_node.kind() == MdParseEnum::Details {
-        return parse_details(parse_node);
+    match parse_node.kind() {
+        MdParseEnum::Details => parse_details(parse_node),
+        MdParseEnum::Quote => callouts::parse_quote(parse_node),
+        MdParseEnum::Embed => embeds::components(parse_node),
+        MdParseEnum::QuoteContainer | MdParseEnum::ListItemContainer => parse_container(parse_node),
+        _ => vec![parse_component(parse_node)],
+    }
+}
+
+/// Parse the blocks of a list item or quote and mark them as nested in it.
+fn parse_container(parse_node: ParseNode) -> Vec<Component> {
+    let (container, marker) = if parse_node.kind() == MdParseEnum::ListItemContainer {
+        (Container::ListItem(parse_node.content().len() as u16), None)
+    } else {
+        (
+            Container::Quote(None),
+            callouts::marker(parse_node.content()),
+        )
+    };
+
+    let mut components: Vec<Component> = parse_node
+        .children_owned()
+        .into_iter()
+        .flat_map(parse_components)
+        .collect();
+    if let Some(marker) = marker {
+        return callouts::components(&marker, components);
+    }
+    for c in &mut components {
+        if let Component::TextComponent(tc) = c {
+            tc.prepend_container(container);
+        }
     }
-    vec![parse_component(parse_node)]
+    components
 }
 
 fn parse_details(parse_node: ParseNode) -> Vec<Component> {
@@ -149,6 +603,7 @@ fn parse_details(parse_node: ParseNode) -> Vec<Component> {
             id,
             folded,
             body_len,
+            callout: None,
         },
         vec![Word::new(header_text, WordType::Normal)],
     )));
@@ -275,11 +730,18 @@ fn parse_component(parse_node: ParseNode) -> Component {
         }
 
         MdParseEnum::Heading => {
-            let indent = parse_node
+            let indent = match parse_node
                 .content()
                 .chars()
                 .take_while(|c| *c == '#')
-                .count();
+                .count()
+            {
+                // Setext headings are underlined with `=` for level 1 and `-`
+                // for level 2
+                0 if parse_node.content().trim_end().ends_with('=') => 1,
+                0 => 2,
+                level => level,
+            };
             let leaf_nodes = get_leaf_nodes(parse_node);
             let mut words = Vec::new();
 
@@ -326,34 +788,53 @@ fn parse_component(parse_node: ParseNode) -> Component {
                 w.set_content(w.content().trim_start().to_owned());
             }
 
+            // Without an explicit id the heading is linked to by its slug,
+            // made unique once the whole document is parsed
+            if !words
+                .iter()
+                .any(|w| w.kind() == WordType::MetaInfo(MetaData::HeadingId))
+            {
+                let text: String = words
+                    .iter()
+                    .skip(usize::from(indent > 1) + 1)
+                    .filter(|w| w.is_renderable())
+                    .map(Word::content)
+                    .collect();
+                words.push(Word::new(
+                    heading_slug(&text),
+                    WordType::MetaInfo(MetaData::HeadingSlug),
+                ));
+            }
+
             Component::TextComponent(TextComponent::new(TextNode::Heading, words))
         }
 
         MdParseEnum::Paragraph => {
-            let leaf_nodes = get_leaf_nodes(parse_node);
-            let mut words = Vec::new();
-            for node in leaf_nodes {
-                let word_type = WordType::from(node.kind());
-                let mut content = node.content().to_owned();
-
-                if matches!(node.kind(), MdParseEnum::WikiLink | MdParseEnum::InlineLink) {
-                    let comp = Word::new(content.clone(), WordType::LinkData);
-                    words.push(comp);
-                }
-
-                if content.starts_with(' ') {
-                    content.remove(0);
-                    let comp = Word::new(" ".to_owned(), word_type);
-                    words.push(comp);
-                }
-                words.push(Word::new(content, word_type));
-            }
+            let mut words = leaf_words(get_leaf_nodes(parse_node), false);
             if let Some(w) = words.first_mut() {
                 w.set_content(w.content().trim_start().to_owned());
             }
             Component::TextComponent(TextComponent::new(TextNode::Paragraph, words))
         }
 
+        MdParseEnum::DefinitionList => {
+            let mut rows = Vec::new();
+            let mut terms = Vec::new();
+            for child in parse_node.children_owned() {
+                terms.push(child.kind() == MdParseEnum::DefinitionTerm);
+                // Continuation lines keep their indentation in front
+                let mut words = leaf_words(get_leaf_nodes(child), true);
+                if let Some(w) = words.iter_mut().find(|w| w.is_renderable()) {
+                    w.set_content(w.content().trim_start().to_owned());
+                }
+                rows.push(words);
+            }
+            Component::TextComponent(TextComponent::new_formatted(
+                TextNode::DefinitionList(terms),
+                rows,
+            ))
+        }
+
         MdParseEnum::CodeBlock => {
             let leaf_nodes = get_leaf_nodes(parse_node);
             let mut words = Vec::new();
@@ -371,7 +852,7 @@ fn parse_component(parse_node: ParseNode) -> Component {
 
             if space_indented {
                 words.push(vec![Word::new(
-                    " ".to_owned(),
+                    "\n".to_owned(),
                     WordType::CodeBlock(Color::Reset),
                 )]);
             }
@@ -430,8 +911,10 @@ fn parse_component(parse_node: ParseNode) -> Component {
         MdParseEnum::Table => {
             let mut words = Vec::new();
             let mut meta_info = Vec::new();
+            let mut alignments = Vec::new();
             for cell in parse_node.children_owned() {
                 if cell.kind() == MdParseEnum::TableSeparator {
+                    alignments.push(column_alignment(cell.content()));
                     meta_info.push(Word::new(
                         cell.content().to_owned(),
                         WordType::MetaInfo(MetaData::ColumnsCount),
@@ -465,7 +948,7 @@ fn parse_component(parse_node: ParseNode) -> Component {
                 words.push(inner_words);
             }
             Component::TextComponent(TextComponent::new_formatted_with_meta(
-                TextNode::Table(vec![], vec![]),
+                TextNode::Table(vec![], vec![], alignments),
                 words,
                 meta_info,
             ))
@@ -479,22 +962,62 @@ fn parse_component(parse_node: ParseNode) -> Component {
             Vec::new(),
         )),
         MdParseEnum::Footnote => {
-            let mut words = Vec::new();
-            let foot_ref = parse_node.children().first().unwrap().to_owned();
-            words.push(Word::new(foot_ref.content, WordType::FootnoteData));
-            let _rest = parse_node
-                .children_owned()
-                .into_iter()
-                .skip(1)
-                .map(|e| e.content)
-                .collect::<String>();
-            words.push(Word::new(_rest, WordType::Footnote));
+            let mut children = parse_node.children_owned().into_iter();
+            let key = children.next().map(|c| c.content).unwrap_or_default();
+            let number = key.trim_matches(['[', ']']);
+            let mut words = vec![
+                Word::new(key.clone(), WordType::FootnoteData),
+                Word::new(format!("{number}. "), WordType::ListMarker),
+            ];
+            words.extend(leaf_words(children.flat_map(get_leaf_nodes), true));
+            if let Some(w) = words.get_mut(2) {
+                w.set_content(w.content().trim_start().to_owned());
+            }
             Component::TextComponent(TextComponent::new(TextNode::Footnote, words))
         }
         _ => todo!("Not implemented for {:?}", parse_node.kind()),
     }
 }
 
+/// The words of the leaf nodes of a block. Links are preceded by their
+/// target and a leading space becomes a word of its own, `squash_spaces`
+/// turns runs of spaces into one.
+fn leaf_words(leaf_nodes: impl IntoIterator<Item = ParseNode>, squash_spaces: bool) -> Vec<Word> {
+    let mut words = Vec::new();
+    for node in leaf_nodes {
+        let word_type = WordType::from(node.kind());
+        let mut content: String = if squash_spaces {
+            node.content()
+                .chars()
+                .dedup_by(|x, y| *x == ' ' && *y == ' ')
+                .collect()
+        } else {
+            node.content().to_owned()
+        };
+
+        if matches!(node.kind(), MdParseEnum::WikiLink | MdParseEnum::InlineLink) {
+            words.push(Word::new(content.clone(), WordType::LinkData));
+        }
+
+        if content.starts_with(' ') {
+            content.remove(0);
+            words.push(Word::new(" ".to_owned(), word_type));
+        }
+        words.push(Word::new(content, word_type));
+    }
+    words
+}
+
+/// `:--` aligns a column left, `:-:` centers it and `--:` aligns it right
+fn column_alignment(separator: &str) -> Alignment {
+    let separator = separator.trim_matches(|c: char| c == '|' || c.is_whitespace());
+    match (separator.starts_with(':'), separator.ends_with(':')) {
+        (true, true) => Alignment::Center,
+        (false, true) => Alignment::Right,
+        _ => Alignment::Left,
+    }
+}
+
 fn get_leaf_nodes(node: ParseNode) -> Vec<ParseNode> {
     let mut leaf_nodes = Vec::new();
 
@@ -642,53 +1165,90 @@ impl ParseNode {
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum MdParseEnum {
+    /// A use of an abbreviation, or its label in a definition, see
+    /// `abbreviations`
+    Abbreviation,
+    /// A `*[label]: title` definition, removed once abbreviations are resolved
+    AbbreviationDefinition,
+    AbbreviationTitle,
     AltText,
     BlockSeparator,
     Bold,
     BoldItalic,
     BoldItalicStr,
     BoldStr,
-    Caution,
+    /// The `[!type]` line a callout starts with, see `callouts`
+    Callout,
     Code,
     CodeBlock,
     CodeBlockStr,
     CodeBlockStrSpaceIndented,
     CodeStr,
+    /// A definition of a term in a definition list
+    Definition,
+    DefinitionList,
+    DefinitionTerm,
     Details,
     DetailsBody,
     DetailsOpenAttr,
     DetailsSummary,
     Digit,
+    /// An `![[note]]` embed, see `embeds`
+    Embed,
+    /// A `:shortcode:` emoji, see `extensions`
+    Emoji,
     FootnoteRef,
     Footnote,
+    /// A line break inside a block
+    HardBreak,
     Heading,
+    /// An explicit `{#id}` of a heading
+    HeadingId,
     HorizontalSeparator,
+    /// A raw inline HTML tag, turned into words by `html`
+    HtmlTag,
     Image,
-    Imortant,
     Indent,
     InlineLink,
     Italic,
     ItalicStr,
+    Kbd,
     Link,
     LinkData,
+    /// The label of a reference link or definition, see `references`
+    LinkReference,
+    /// A `[label]: url "title"` definition, removed once references are
+    /// resolved
+    LinkReferenceDefinition,
+    /// A link title, moved into the root's titles when references are
+    /// resolved
+    LinkTitle,
     ListContainer,
-    Note,
+    /// Blocks inside a list item, see `containers`. Not produced by the
+    /// grammar.
+    ListItemContainer,
+    /// `==marked==` text
+    Mark,
     OrderedList,
     PLanguage,
     Paragraph,
     Quote,
+    /// Blocks inside a quote, see `containers`. Not produced by the grammar.
+    QuoteContainer,
     Sentence,
     Strikethrough,
     StrikethroughStr,
+    Subscript,
+    Superscript,
     Table,
     TableCell,
     TableSeparator,
+    /// An Obsidian `#tag`
+    Tag,
     Task,
     TaskClosed,
     TaskOpen,
-    Tip,
     UnorderedList,
-    Warning,
     WikiLink,
     Word,
 }
@@ -696,7 +1256,9 @@ pub enum MdParseEnum {
 impl From<Rule> for MdParseEnum {
     fn from(value: Rule) -> Self {
         match value {
-            Rule::word | Rule::h_word | Rule::latex_word | Rule::t_word => Self::Word,
+            Rule::word | Rule::h_word | Rule::latex_word | Rule::t_word | Rule::setext_h_word => {
+                Self::Word
+            }
             Rule::indent => Self::Indent,
             Rule::italic_word_var_1 | Rule::italic_word_var_2 => Self::Italic,
             Rule::italic_var_1 | Rule::italic_var_2 => Self::ItalicStr,
@@ -727,6 +1289,9 @@ impl From<Rule> for MdParseEnum {
             Rule::h1 | Rule::h2 | Rule::h3 | Rule::h4 | Rule::h5 | Rule::h6 | Rule::heading => {
                 Self::Heading
             }
+            Rule::heading_id => Self::HeadingId,
+            Rule::html => Self::HtmlTag,
+            Rule::hard_break => Self::HardBreak,
             Rule::list_container => Self::ListContainer,
             Rule::paragraph => Self::Paragraph,
             Rule::code_block | Rule::indented_code_block => Self::CodeBlock,
@@ -735,16 +1300,25 @@ impl From<Rule> for MdParseEnum {
             Rule::task => Self::Task,
             Rule::block_sep => Self::BlockSeparator,
             Rule::horizontal_sep => Self::HorizontalSeparator,
-            Rule::link_data | Rule::wiki_link_data => Self::LinkData,
+            Rule::link_data | Rule::wiki_link_data | Rule::link_ref_url | Rule::embed_target => {
+                Self::LinkData
+            }
+            Rule::link_ref | Rule::link_ref_label | Rule::link_shortcut => Self::LinkReference,
+            Rule::link_ref_def => Self::LinkReferenceDefinition,
+            Rule::link_title_double | Rule::link_title_single | Rule::link_title_paren => {
+                Self::LinkTitle
+            }
+            Rule::definition_list => Self::DefinitionList,
+            Rule::definition_term => Self::DefinitionTerm,
+            Rule::definition => Self::Definition,
+            Rule::abbreviation_def => Self::AbbreviationDefinition,
+            Rule::abbreviation_label => Self::Abbreviation,
+            Rule::abbreviation_title => Self::AbbreviationTitle,
             Rule::details => Self::Details,
             Rule::details_body => Self::DetailsBody,
             Rule::details_open_attr => Self::DetailsOpenAttr,
             Rule::summary | Rule::summary_text => Self::DetailsSummary,
-            Rule::warning => Self::Warning,
-            Rule::note => Self::Note,
-            Rule::tip => Self::Tip,
-            Rule::important => Self::Imortant,
-            Rule::caution => Self::Caution,
+            Rule::callout => Self::Callout,
             Rule::p_char
             | Rule::t_char
             | Rule::link_char
@@ -761,8 +1335,9 @@ impl From<Rule> for MdParseEnum {
             | Rule::list_prefix
             | Rule::forbidden_sentence_prefix => Self::Paragraph,
             Rule::image => Self::Image,
+            Rule::embed => Self::Embed,
             Rule::alt_word | Rule::alt_text => Self::AltText,
-            Rule::footnote_ref => Self::FootnoteRef,
+            Rule::footnote_ref | Rule::footnote_ref_container => Self::FootnoteRef,
             Rule::footnote => Self::Footnote,
             Rule::heading_prefix
             | Rule::alt_char
@@ -778,11 +1353,29 @@ impl From<Rule> for MdParseEnum {
             | Rule::s_char
             | Rule::WHITESPACE_S
             | Rule::wiki_link
-            | Rule::footnote_ref_container
+            | Rule::link_destination
+            | Rule::link_title
+            | Rule::setext_line
+            | Rule::setext_continuation
+            | Rule::setext_underline
+            | Rule::setext_heading
+            | Rule::heading_id_wrapper
+            | Rule::html_newline
+            | Rule::html_tag_name
+            | Rule::html_space
+            | Rule::html_attribute_value
+            | Rule::html_attribute
+            | Rule::html_tag
             | Rule::details_open_tag
             | Rule::details_close_tag
             | Rule::summary_open_tag
-            | Rule::summary_close_tag => todo!(),
+            | Rule::summary_close_tag
+            | Rule::escape
+            | Rule::line_end
+            | Rule::definition_line
+            | Rule::definition_prefix
+            | Rule::definition_item
+            | Rule::EOI => todo!(),
         }
     }
 }
@@ -790,7 +1383,7 @@ impl From<Rule> for MdParseEnum {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::nodes::textcomponent::TextNode;
+    use crate::nodes::{callout::Callout, textcomponent::TextNode};
 
     fn component_kinds(md: &str) -> Vec<TextNode> {
         parse_markdown(None, md, 80)
@@ -929,7 +1522,7 @@ mod tests {
         );
         let table_count = kinds
             .iter()
-            .filter(|k| matches!(k, TextNode::Table(_, _)))
+            .filter(|k| matches!(k, TextNode::Table(..)))
             .count();
         assert_eq!(
             table_count, 2,
@@ -1194,4 +1787,410 @@ mod tests {
             "## Many spaces here"
         );
     }
+
+    #[test]
+    fn setext_headings() {
+        assert_eq!(heading_text("Title\n=====\n"), "Title");
+        assert_eq!(heading_text("intro\n\nSub title\n---\n"), "## Sub title");
+        assert!(component_kinds("a\n\n---\n").contains(&TextNode::HorizontalSeparator));
+    }
+
+    #[test]
+    fn explicit_heading_id_is_hidden_and_is_an_anchor() {
+        let md = "# Intro\n\ntext\n\n## Other heading {#custom-id}\n";
+        assert_eq!(
+            heading_text("## Other heading {#custom-id}\n"),
+            "## Other heading"
+        );
+
+        let mut root = parse_markdown(None, md, 80);
+        assert!(
+            root.heading_offset("#custom-id")
+                .is_ok_and(|offset| offset > 0)
+        );
+        assert!(root.heading_offset("#other-heading").is_err());
+    }
+
+    #[test]
+    fn duplicate_headings_get_numbered_slugs() {
+        let md = "# Usage\n\na\n\n## Usage\n\nb\n\n### Usage\n\nc\n";
+        let mut root = parse_markdown(None, md, 80);
+        let anchors: Vec<_> = root
+            .components()
+            .into_iter()
+            .filter_map(TextComponent::heading_anchor)
+            .collect();
+        assert_eq!(anchors, ["usage", "usage-1", "usage-2"]);
+
+        let first = root.heading_offset("#usage").unwrap();
+        let second = root.heading_offset("#usage-1").unwrap();
+        assert!(first < second);
+        assert_eq!(root.heading_offset("#Usage"), Ok(first));
+        assert_eq!(root.heading_offset("#USAGE-1"), Ok(second));
+    }
+
+    #[test]
+    fn split_blocks_keeps_fenced_code_together() {
+        let md = "first\nstill first\n\n```\ncode\n\nmore code\n```\n\nlast\n";
+        let blocks = split_blocks(md);
+        assert_eq!(
+            blocks,
+            vec![
+                (0, "first\nstill first"),
+                (3, "```\ncode\n\nmore code\n```"),
+                (9, "last"),
+            ]
+        );
+    }
+
+    #[test]
+    fn unparseable_block_falls_back_to_plain_text() {
+        // Unclosed `**` backtrack past the call limit
+        let bad = "**a ".repeat(500);
+        let md = format!("Good paragraph.\n\n{bad}\n\nMiddle.\n\n{bad}\n\nAnother one.\n");
+
+        let root = parse_markdown(None, &md, 80);
+        let lines: Vec<usize> = root.parse_errors().iter().map(|e| e.line).collect();
+        assert_eq!(lines, [3, 7], "every fallback block is reported");
+
+        let kinds: Vec<TextNode> = root.components().iter().map(|c| c.kind()).collect();
+        assert_eq!(
+            kinds.iter().filter(|k| **k == TextNode::Paragraph).count(),
+            3,
+            "good blocks should still parse, got {kinds:?}"
+        );
+        let code: Vec<String> = root
+            .components()
+            .into_iter()
+            .filter(|c| c.kind() == TextNode::CodeBlock)
+            .map(|c| c.content_as_lines().concat().replace('\n', ""))
+            .collect();
+        assert_eq!(
+            code.len(),
+            2,
+            "bad blocks should be shown as preformatted text"
+        );
+        assert!(code.iter().all(|code| code.starts_with("**a **a")));
+    }
+
+    #[test]
+    fn call_limit_is_sized_by_block() {
+        let block = "word ".repeat(2000);
+        let one = call_limit(&block) - MIN_CALLS;
+        let many = call_limit(&format!("{block}\n\n").repeat(10)) - MIN_CALLS;
+        assert_eq!(many, 10 * one, "blocks don't add to each other's limit");
+    }
+
+    #[test]
+    fn valid_document_has_no_diagnostics() {
+        assert!(diagnose("# Title\n\nSome *text*.\n").is_empty());
+        assert!(diagnose("").is_empty());
+        assert!(
+            parse_markdown(None, "# Title\n", 80)
+                .parse_errors()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn escapes_and_entities_are_decoded_outside_code() {
+        let md =
+            "\\*not italic\\* &amp; &#x2192; `&amp;`\n\n| a \\| b | c |\n|---|---|\n| x | y |\n";
+        let root = parse_markdown(None, md, 80);
+        let components = root.components();
+        let text = components[0].content_as_lines().join("");
+        assert_eq!(text, "*not italic* & → &amp;");
+
+        let table = components
+            .iter()
+            .find(|c| matches!(c.kind(), TextNode::Table(..)))
+            .expect("escaped pipe keeps the table intact");
+        assert!(table.content_as_lines().join("").contains("a | b"));
+    }
+
+    #[test]
+    fn table_columns_keep_their_alignment() {
+        let md = "| a | b | c | d |\n|:--|:-:|--:|---|\n| 1 | 2 | 3 | 4 |\n";
+        let kinds = component_kinds(md);
+        let Some(TextNode::Table(_, _, alignments)) = kinds.first() else {
+            panic!("expected a table, got {kinds:?}");
+        };
+        assert_eq!(
+            alignments,
+            &[
+                Alignment::Left,
+                Alignment::Center,
+                Alignment::Right,
+                Alignment::Left
+            ]
+        );
+    }
+
+    #[test]
+    fn tables_interrupt_paragraphs() {
+        let md = "Hello World\n| abc | def |\n| --- | --- |\n| bar | baz |\n";
+        let kinds = component_kinds(md);
+        assert!(
+            matches!(
+                kinds.as_slice(),
+                [TextNode::Paragraph, .., TextNode::Table(..)]
+            ),
+            "expected a paragraph and a table, got {kinds:?}"
+        );
+    }
+
+    #[test]
+    fn table_mode_sorts_and_filters_rows() {
+        let md = "| name | size |\n|---|---|\n| b | 10 |\n| a | 9 |\n| c | 100 |\n";
+        let mut root = parse_markdown(None, md, 80);
+        root.select_table(0, 80).expect("select_table");
+        let table = root.selected_table_mut().expect("a table in table mode");
+
+        table.move_table_cursor(0, 1);
+        table.sort_table(80);
+        let names =
+            |rows: Vec<Vec<String>>| rows.into_iter().map(|r| r[0].clone()).collect::<Vec<_>>();
+        assert_eq!(names(table.table_rows()), ["name", "a", "b", "c"]);
+
+        table.sort_table(80);
+        table.filter_table("0", 80);
+        assert_eq!(names(table.table_rows()), ["name", "c", "b"]);
+
+        root.deselect_table(80);
+        let table = root
+            .components()
+            .into_iter()
+            .find(|c| matches!(c.kind(), TextNode::Table(..)))
+            .expect("a table");
+        assert_eq!(table.content().len(), 8);
+    }
+
+    #[test]
+    fn hard_breaks_end_lines_and_soft_breaks_do_not() {
+        let root = parse_markdown(None, "John Doe  \n12 Main St.\\\nSpringfield \nUSA\n", 80);
+        let paragraph = root.components().into_iter().next().expect("a paragraph");
+        assert_eq!(paragraph.height(), 3);
+
+        let root = parse_markdown(None, "trailing\\\n\nspaces  \n", 80);
+        let words: String = root.words().iter().map(|w| w.content()).collect();
+        assert_eq!(words, "trailing\\spaces");
+    }
+
+    #[test]
+    fn definition_lists_indent_definitions_under_their_terms() {
+        let md = "Apple\n: A fruit\n: A company\n\nOrange\n:   The fruit of the citrus tree,\n    continued\n";
+        let root = parse_markdown(None, md, 80);
+        let list = root
+            .components()
+            .into_iter()
+            .find(|c| matches!(c.kind(), TextNode::DefinitionList(_)))
+            .expect("a definition list");
+
+        let TextNode::DefinitionList(terms) = list.kind() else {
+            unreachable!()
+        };
+        assert_eq!(terms, [true, false, false, false, true, false]);
+        assert_eq!(
+            list.content_as_lines(),
+            [
+                "Apple",
+                "    A fruit",
+                "    A company",
+                "",
+                "Orange",
+                "    The fruit of the citrus tree, continued",
+            ]
+        );
+    }
+
+    #[test]
+    fn definition_lists_narrower_than_their_indent_still_wrap() {
+        let md = "Term\n: A definition\n";
+        for width in [1, 3, 4, 5] {
+            let root = parse_markdown(None, md, width);
+            let list = root
+                .components()
+                .into_iter()
+                .next()
+                .expect("a definition list");
+            let words: String = list
+                .content_as_lines()
+                .concat()
+                .split_whitespace()
+                .collect();
+            assert_eq!(words, "TermAdefinition", "width {width}");
+        }
+    }
+
+    #[test]
+    fn foldable_callouts_fold_like_details() {
+        let md = "> [!faq]- Why?\n> Because.\n\n> [!example] Lists\n> - one\n> - two\n";
+        let mut root = parse_markdown(None, md, 80);
+        let kinds: Vec<_> = root.components().iter().map(|c| c.kind()).collect();
+        assert!(matches!(
+            kinds[0],
+            TextNode::DetailsSummary {
+                folded: true,
+                callout: Some(Callout::Question),
+                ..
+            }
+        ));
+        assert!(kinds.contains(&TextNode::CalloutTitle(Callout::Example)));
+        let body = |root: &ComponentRoot| {
+            root.components()
+                .into_iter()
+                .find(|c| c.content_as_lines() == ["Because."])
+                .is_some_and(|c| !c.is_hidden())
+        };
+        assert!(!body(&root));
+
+        root.select_details(0).unwrap();
+        root.toggle_selected_details().unwrap();
+        assert!(body(&root));
+    }
+
+    #[test]
+    fn headings_fold_their_sections() {
+        let md = "# Spec
+
+Intro.
+
+## One
+
+First.
+
+### Deep
+
+Deeper.
+
+## Two
+
+Second.
+";
+        let mut root = parse_markdown(None, md, 80);
+        let visible = |root: &ComponentRoot| -> Vec<String> {
+            root.components()
+                .into_iter()
+                .filter(|c| !c.is_hidden() && c.kind() != TextNode::LineBreak)
+                .flat_map(|c| c.content_as_lines())
+                .collect()
+        };
+        let height = root.height();
+
+        root.fold_headings(Some(2), 0);
+        assert_eq!(visible(&root), ["Spec", "Intro.", "## One", "## Two"]);
+        let folded = |root: &ComponentRoot, title: &str| {
+            root.components()
+                .into_iter()
+                .find(|c| c.content_as_lines() == [title])
+                .and_then(|c| c.folded_lines())
+        };
+        assert_eq!(folded(&root, "## One"), Some(7));
+        assert_eq!(folded(&root, "## Two"), Some(3));
+
+        // Opening `One` keeps `Deep` folded
+        root.set_scroll(0);
+        let one = root.heading_offset("#one").unwrap();
+        assert_eq!(root.toggle_section_at(one), Ok(one));
+        assert_eq!(
+            visible(&root),
+            ["Spec", "Intro.", "## One", "First.", "### Deep", "## Two"]
+        );
+
+        root.fold_headings(None, 0);
+        assert_eq!(root.height(), height);
+    }
+
+    #[test]
+    fn jumping_into_a_folded_section_opens_it() {
+        let md =
+            "# Spec\n\nIntro.\n\n## One\n\nFirst.\n\n### Deep\n\nDeeper.\n\n## Two\n\nSecond.\n";
+        let mut root = parse_markdown(None, md, 80);
+        root.fold_all(true, 0);
+        let deep = root.heading_offset("#deep").unwrap();
+
+        let lines: Vec<String> = root
+            .components()
+            .into_iter()
+            .filter(|c| !c.is_hidden())
+            .flat_map(|c| c.content_as_lines())
+            .collect();
+        assert_eq!(lines[usize::from(deep)], "### Deep");
+        let visible: Vec<&String> = lines.iter().filter(|line| !line.is_empty()).collect();
+        assert_eq!(
+            visible,
+            ["Spec", "Intro.", "## One", "First.", "### Deep", "## Two"]
+        );
+    }
+
+    #[test]
+    fn fold_state_survives_a_reparse() {
+        let md = "<details>\n<summary>More about the long summary</summary>\n\nOne\n</details>\n\n\
+                  <details open>\n<summary>More about the long summary</summary>\n\nTwo\n</details>\n\n\
+                  ## Section\n\nText\n";
+        let folded = |root: &ComponentRoot| -> Vec<bool> {
+            root.components()
+                .into_iter()
+                .filter_map(|c| match c.kind() {
+                    TextNode::DetailsSummary { folded, .. } => Some(folded),
+                    TextNode::Heading => Some(c.is_section_folded()),
+                    _ => None,
+                })
+                .collect()
+        };
+        let mut root = parse_markdown(None, md, 80);
+        assert_eq!(folded(&root), [true, false, false]);
+
+        root.fold_all(true, 0);
+        assert_eq!(folded(&root), [true, true, true]);
+        root.select_details(0).unwrap();
+        root.toggle_selected_details().unwrap();
+
+        // Narrow enough to wrap the summaries
+        let mut reparsed = parse_markdown(None, md, 20);
+        reparsed.restore_fold_state(&root.fold_state());
+        assert_eq!(folded(&reparsed), [false, true, true]);
+        assert_eq!(reparsed.fold_state(), root.fold_state());
+    }
+
+    #[test]
+    fn indented_code_blocks_keep_their_lines_without_the_indent() {
+        let root = parse_markdown(None, "Text\n\n    indented\n\n      code\n", 80);
+        let code = root
+            .components()
+            .into_iter()
+            .find(|c| matches!(c.kind(), TextNode::CodeBlock))
+            .expect("a code block");
+        let lines: Vec<String> = code
+            .content_as_lines()
+            .iter()
+            .map(|line| line.replace('\n', ""))
+            .collect();
+        // Not "     indented", " ", "       code" with the indent and the
+        // blank lines as spaces, like a fenced block shows them
+        assert_eq!(lines, ["", "indented", "", "  code", ""]);
+        assert_eq!(
+            String::from_utf8(code.content_as_bytes()).unwrap(),
+            "\nindented\n\n  code\n"
+        );
+    }
+
+    #[test]
+    fn code_blocks_and_sections_are_found_for_yanking() {
+        let md = "# Top\n\n```rust\nfn main() {\n    println!(\"hi\");\n}\n```\n\n\
+                  ## Sub heading\n\nText\n";
+        let code = |root: &ComponentRoot, scroll| {
+            root.code_block_at(scroll, 3)
+                .map(|code| String::from_utf8(code.content_as_bytes()).unwrap())
+        };
+        let root = parse_markdown(None, md, 80);
+        assert_eq!(
+            code(&root, 0).as_deref(),
+            Some("\nfn main() {\n    println!(\"hi\");\n}\n")
+        );
+        assert_eq!(code(&root, 20), None);
+
+        assert_eq!(root.section_anchor(0), Some("top"));
+        assert_eq!(root.section_anchor(9), Some("sub-heading"));
+    }
 }
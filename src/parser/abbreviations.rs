@@ -0,0 +1,165 @@
+//! Abbreviations, as in PHP Markdown Extra. `*[HTML]: Hyper Text Markup
+//! Language` says what `HTML` stands for everywhere in the document, also
+//! before the definition. Uses become words of their own, which are selected
+//! like links to show what they stand for.
+
+use std::{cmp::Reverse, collections::HashMap};
+
+use itertools::Itertools;
+
+use super::{MdParseEnum, ParseNode};
+
+/// Split the uses of abbreviations out of plain words and drop the
+/// definitions. Returns what each abbreviation stands for.
+pub(super) fn resolve(nodes: Vec<ParseNode>) -> (Vec<ParseNode>, HashMap<String, String>) {
+    let mut titles = HashMap::new();
+    collect_definitions(&nodes, &mut titles);
+    if titles.is_empty() {
+        return (nodes, titles);
+    }
+
+    // The longest abbreviation wins, `C++` over `C`
+    let labels = titles
+        .keys()
+        .cloned()
+        .sorted_by_key(|label| Reverse(label.len()))
+        .collect_vec();
+    (resolve_children(nodes, &labels), titles)
+}
+
+/// Parse a `*[label]: title` line. The grammar does this for the pest
+/// backend, pulldown-cmark doesn't know about abbreviations.
+#[cfg(feature = "commonmark")]
+pub(super) fn definition(line: &str) -> Option<ParseNode> {
+    let line = line.trim_end();
+    let rest = line.trim_start_matches(' ');
+    if line.len() - rest.len() > 3 {
+        return None;
+    }
+    let (label, title) = rest.strip_prefix("*[")?.split_once("]:")?;
+    if label.is_empty() || label.contains(']') {
+        return None;
+    }
+
+    let mut node = ParseNode::new(MdParseEnum::AbbreviationDefinition, line.to_owned());
+    node.add_children(vec![
+        ParseNode::new(MdParseEnum::Abbreviation, label.to_owned()),
+        ParseNode::new(MdParseEnum::AbbreviationTitle, title.trim().to_owned()),
+    ]);
+    Some(node)
+}
+
+fn collect_definitions(nodes: &[ParseNode], titles: &mut HashMap<String, String>) {
+    for node in nodes {
+        if node.kind() != MdParseEnum::AbbreviationDefinition {
+            collect_definitions(node.children(), titles);
+            continue;
+        }
+
+        let mut label = None;
+        let mut title = String::new();
+        for child in node.children() {
+            match child.kind() {
+                MdParseEnum::Abbreviation => label = Some(child.content().trim().to_owned()),
+                MdParseEnum::AbbreviationTitle => child.content().trim().clone_into(&mut title),
+                _ => {}
+            }
+        }
+
+        // The first definition of an abbreviation wins
+        if let Some(label) = label.filter(|l| !l.is_empty()) {
+            titles.entry(label).or_insert(title);
+        }
+    }
+}
+
+fn resolve_children(nodes: Vec<ParseNode>, labels: &[String]) -> Vec<ParseNode> {
+    let mut resolved = Vec::with_capacity(nodes.len());
+    for mut node in nodes {
+        match node.kind() {
+            MdParseEnum::AbbreviationDefinition => {}
+            MdParseEnum::Word if node.children().is_empty() => {
+                resolved.extend(split_word(node, labels));
+            }
+            _ => {
+                let children = std::mem::take(&mut node.children);
+                node.children = resolve_children(children, labels);
+                resolved.push(node);
+            }
+        }
+    }
+
+    // Dropped definitions leave the blank lines around them behind
+    resolved
+        .into_iter()
+        .dedup_by(|a, b| {
+            a.kind() == MdParseEnum::BlockSeparator && b.kind() == MdParseEnum::BlockSeparator
+        })
+        .collect()
+}
+
+/// Split a word around the abbreviations in it. An abbreviation only counts
+/// as a whole word, `HTML` isn't found in `XHTML`.
+fn split_word(node: ParseNode, labels: &[String]) -> Vec<ParseNode> {
+    let text = node.content();
+    let is_boundary = |c: Option<char>| c.is_none_or(|c| !c.is_alphanumeric());
+
+    let mut nodes = Vec::new();
+    let mut plain_start = 0;
+    let mut i = 0;
+    while let Some(c) = text[i..].chars().next() {
+        let found = labels.iter().find(|label| {
+            text[i..].starts_with(label.as_str())
+                && is_boundary(text[..i].chars().next_back())
+                && is_boundary(text[i + label.len()..].chars().next())
+        });
+        if let Some(label) = found {
+            if plain_start < i {
+                nodes.push(ParseNode::new(
+                    MdParseEnum::Word,
+                    text[plain_start..i].to_owned(),
+                ));
+            }
+            nodes.push(ParseNode::new(MdParseEnum::Abbreviation, label.clone()));
+            i += label.len();
+            plain_start = i;
+        } else {
+            i += c.len_utf8();
+        }
+    }
+
+    if nodes.is_empty() {
+        return vec![node];
+    }
+    if plain_start < text.len() {
+        nodes.push(ParseNode::new(
+            MdParseEnum::Word,
+            text[plain_start..].to_owned(),
+        ));
+    }
+    nodes
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{nodes::word::WordType, parser::parse_markdown};
+
+    #[test]
+    fn abbreviations_are_split_out_and_definitions_dropped() {
+        let md = "The HTML spec, not XHTML.\n\n*[HTML]: Hyper Text Markup Language\n";
+        let root = parse_markdown(None, md, 80);
+        let words: Vec<_> = root
+            .words()
+            .into_iter()
+            .map(|w| (w.content().to_owned(), w.kind()))
+            .collect();
+
+        assert!(words.contains(&("HTML".to_owned(), WordType::Abbreviation)));
+        assert!(words.contains(&("XHTML.".to_owned(), WordType::Normal)));
+        assert!(!words.iter().any(|(w, _)| w.contains("*[")));
+        assert_eq!(
+            root.abbreviation("HTML"),
+            Some("Hyper Text Markup Language")
+        );
+    }
+}
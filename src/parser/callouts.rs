@@ -0,0 +1,147 @@
+//! GitHub alerts and Obsidian callouts, quotes whose first line is a `[!type]`
+//! marker. The rest of that line is the title, shown above the body in the
+//! color of the callout. A `-` or `+` right after the marker makes the callout
+//! foldable, folded or open to begin with, and its body folds like the body of
+//! a `<details>` block.
+
+use crate::nodes::{
+    callout::Callout,
+    root::Component,
+    textcomponent::{Container, TextComponent, TextNode},
+    word::{Word, WordType},
+};
+
+use super::{MdParseEnum, ParseNode, next_details_id, parse_component, tag_owning_details};
+
+/// The parsed `[!type]- Title` line of a callout
+#[derive(Debug, PartialEq, Eq)]
+pub(super) struct Marker {
+    pub callout: Callout,
+    /// `None` if the callout can't be folded
+    pub folded: Option<bool>,
+    pub title: String,
+}
+
+/// Parse the first line of a quote, without the `>`, as a callout marker
+pub(super) fn marker(line: &str) -> Option<Marker> {
+    let (name, rest) = line.trim().strip_prefix("[!")?.split_once(']')?;
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return None;
+    }
+
+    let (folded, rest) = match rest.chars().next() {
+        Some('-') => (Some(true), &rest[1..]),
+        Some('+') => (Some(false), &rest[1..]),
+        _ => (None, rest),
+    };
+    if rest.starts_with(|c: char| !c.is_whitespace()) {
+        return None;
+    }
+
+    // Without a title the type is shown, like `Note` for `[!NOTE]`
+    let title = match rest.trim() {
+        "" => {
+            let mut chars = name.chars();
+            chars.next().map_or_else(String::new, |first| {
+                first
+                    .to_uppercase()
+                    .chain(chars.flat_map(char::to_lowercase))
+                    .collect()
+            })
+        }
+        title => title.to_owned(),
+    };
+
+    Some(Marker {
+        callout: Callout::from_name(name),
+        folded,
+        title,
+    })
+}
+
+/// Split a quote the grammar parsed in one go into the title and body of the
+/// callout it starts with
+pub(super) fn parse_quote(mut parse_node: ParseNode) -> Vec<Component> {
+    let marker = parse_node
+        .children()
+        .first()
+        .filter(|c| c.kind() == MdParseEnum::Callout)
+        .and_then(|c| marker(c.content()));
+    let Some(marker) = marker else {
+        return vec![parse_component(parse_node)];
+    };
+
+    let mut children = std::mem::take(&mut parse_node.children).into_iter().skip(1);
+    let body = children.next().map(|mut first| {
+        // The body starts on the line after the marker
+        first.content = first.content.trim_start().to_owned();
+        let mut paragraph = ParseNode::new(MdParseEnum::Paragraph, String::new());
+        paragraph.add_children(std::iter::once(first).chain(children).collect());
+        parse_component(paragraph)
+    });
+    components(&marker, body.into_iter().collect())
+}
+
+/// Put the title in front of the body of a callout and nest both in it
+pub(super) fn components(marker: &Marker, mut body: Vec<Component>) -> Vec<Component> {
+    let title = match marker.folded {
+        Some(folded) => {
+            let id = next_details_id();
+            tag_owning_details(&mut body, id);
+            TextNode::DetailsSummary {
+                id,
+                folded,
+                body_len: body.len(),
+                callout: Some(marker.callout),
+            }
+        }
+        None => TextNode::CalloutTitle(marker.callout),
+    };
+    let words = vec![Word::new(marker.title.clone(), WordType::Normal)];
+
+    let mut components = vec![Component::TextComponent(TextComponent::new(title, words))];
+    components.extend(body);
+    for c in &mut components {
+        if let Component::TextComponent(tc) = c {
+            tc.prepend_container(Container::Quote(Some(marker.callout)));
+        }
+    }
+    components
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn markers_take_a_fold_and_title() {
+        assert_eq!(
+            marker("[!NOTE]"),
+            Some(Marker {
+                callout: Callout::Note,
+                folded: None,
+                title: "Note".to_owned(),
+            })
+        );
+        assert_eq!(
+            marker(" [!faq]- Why  not? "),
+            Some(Marker {
+                callout: Callout::Question,
+                folded: Some(true),
+                title: "Why  not?".to_owned(),
+            })
+        );
+        assert_eq!(marker("[!tip]+").map(|m| m.folded), Some(Some(false)));
+        assert_eq!(
+            marker("[!whatever]").map(|m| (m.callout, m.title)),
+            Some((Callout::Note, "Whatever".to_owned()))
+        );
+        assert_eq!(marker("[!tip]x"), None);
+        assert_eq!(marker("[!] title"), None);
+        assert_eq!(marker("[link]"), None);
+    }
+}
@@ -0,0 +1,1039 @@
+//! Alternative frontend which builds the parse tree from a CommonMark + GFM
+//! event stream instead of the pest grammar. The resulting `ParseNode`s have
+//! the same shape as the ones produced from `md.pest`, including the
+//! containers `parser/containers.rs` nests quotes and list items in, so
+//! everything from `parse_component` onwards is shared between the two.
+
+use pulldown_cmark::{Alignment, CodeBlockKind, Event, LinkType, Options, Parser, Tag, TagEnd};
+
+use super::{MdParseEnum, ParseNode, abbreviations, callouts, html};
+
+pub(super) fn parse_nodes(content: &str) -> Vec<ParseNode> {
+    let options = Options::ENABLE_TABLES
+        | Options::ENABLE_FOOTNOTES
+        | Options::ENABLE_STRIKETHROUGH
+        | Options::ENABLE_TASKLISTS
+        | Options::ENABLE_GFM
+        | Options::ENABLE_WIKILINKS
+        | Options::ENABLE_HEADING_ATTRIBUTES
+        | Options::ENABLE_SUPERSCRIPT
+        | Options::ENABLE_SUBSCRIPT
+        | Options::ENABLE_DEFINITION_LIST;
+
+    let mut builder = TreeBuilder::default();
+    for (event, range) in Parser::new_ext(content, options).into_offset_iter() {
+        match event {
+            Event::Start(Tag::Paragraph) => {
+                builder.paragraph_source = Some(content[range].to_owned());
+            }
+            Event::Start(Tag::BlockQuote(_)) => {
+                builder.quote_line = content[range].lines().next().map(str::to_owned);
+            }
+            Event::Start(Tag::Item) => {
+                builder.item_column = Some(item_column(content, range.start));
+            }
+            _ => {}
+        }
+        builder.event(event);
+    }
+    let mut nodes = builder.finish();
+    // The pest grammar ends a document with a trailing newline in a block
+    // separator, keep the same spacing at the bottom of the page. A container
+    // at the end keeps the newline in its content instead.
+    let ends_in_container = nodes.last().is_some_and(|node| {
+        matches!(
+            node.kind(),
+            MdParseEnum::QuoteContainer | MdParseEnum::ListItemContainer
+        )
+    });
+    if content.ends_with('\n') && !nodes.is_empty() && !ends_in_container {
+        nodes.push(ParseNode::new(MdParseEnum::BlockSeparator, String::new()));
+    }
+    nodes
+}
+
+struct ListItem {
+    depth: usize,
+    number: Option<u64>,
+    task: Option<bool>,
+    /// The first paragraph ended, blocks after it go in a container
+    paragraph_done: bool,
+}
+
+/// The column the text of the list item whose marker is at `start` begins
+/// at, counted from the `>` of the quote it is in
+fn item_column(content: &str, start: usize) -> usize {
+    let line_start = content[..start].rfind('\n').map_or(0, |i| i + 1);
+    let prefix = &content[line_start..start];
+    let prefix = prefix.rsplit_once('>').map_or(prefix, |(_, rest)| {
+        rest.strip_prefix([' ', '\t']).unwrap_or(rest)
+    });
+    let indent: usize = prefix.chars().map(|c| if c == '\t' { 4 } else { 1 }).sum();
+
+    let text = &content[start..];
+    let marker = match text.chars().next() {
+        Some('-' | '*' | '+') => 1,
+        _ => text.chars().take_while(char::is_ascii_digit).count() + 1,
+    };
+    let spaces = text[marker..].chars().take_while(|c| *c == ' ').count();
+    indent + marker + if (1..=4).contains(&spaces) { spaces } else { 1 }
+}
+
+/// A quote of a single paragraph is a `Quote` like the grammar's, one
+/// holding other blocks a `QuoteContainer`
+fn quote(marker: Option<String>, mut blocks: Vec<ParseNode>) -> ParseNode {
+    if let [paragraph] = blocks.as_slice()
+        && paragraph.kind() == MdParseEnum::Paragraph
+    {
+        let mut node = ParseNode::new(MdParseEnum::Quote, String::new());
+        if let Some(marker) = marker {
+            node.add_children(vec![ParseNode::new(MdParseEnum::Callout, marker)]);
+        }
+        node.add_children(blocks.remove(0).children_owned());
+        return node;
+    }
+    let mut node = ParseNode::new(MdParseEnum::QuoteContainer, marker.unwrap_or_default());
+    node.add_children(blocks);
+    node
+}
+
+enum FrameKind {
+    /// A quote, with its callout marker line
+    Quote(Option<String>),
+    /// The blocks of a list item after its first paragraph, with the column
+    /// its text starts at in the quote or document
+    Item(usize),
+}
+
+/// An open quote or list item that holds blocks. The lists around it are
+/// put aside until it ends, lists inside it start over like in the pest
+/// grammar, which parses a container's content on its own.
+struct Frame {
+    kind: FrameKind,
+    blocks: Vec<ParseNode>,
+    lists: Vec<Option<u64>>,
+    list_items: Vec<ParseNode>,
+    item: Option<ListItem>,
+    item_columns: Vec<usize>,
+}
+
+struct Table {
+    alignments: Vec<Alignment>,
+    cells: Vec<ParseNode>,
+}
+
+struct Details {
+    open: bool,
+    summary: String,
+    body: Vec<ParseNode>,
+}
+
+/// Collects inline content into leaf nodes and emits a block node whenever
+/// a block ends. Blocks in quotes and list items are collected in a frame,
+/// which becomes a `QuoteContainer` or `ListItemContainer` when it ends.
+#[derive(Default)]
+struct TreeBuilder {
+    blocks: Vec<ParseNode>,
+    details: Vec<Details>,
+    frames: Vec<Frame>,
+
+    leaves: Vec<ParseNode>,
+    space: bool,
+    bold: usize,
+    italic: usize,
+    strikethrough: usize,
+    subscript: usize,
+    superscript: usize,
+
+    link: Option<(String, String, String)>,
+    /// Url and alt text of the open image, and whether it is an `![[embed]]`
+    image: Option<(String, String, bool)>,
+    images: Vec<ParseNode>,
+    code_block: Option<(String, String)>,
+    html: Option<String>,
+    /// Level and explicit id of the open heading
+    heading: Option<(usize, Option<String>)>,
+    footnote: Option<String>,
+
+    /// Next number for each open list, `None` for unordered lists
+    lists: Vec<Option<u64>>,
+    list_items: Vec<ParseNode>,
+    item: Option<ListItem>,
+    /// Columns the text of the open list items starts at, innermost last
+    item_columns: Vec<usize>,
+    /// Column of the list item that starts next
+    item_column: Option<usize>,
+
+    table: Option<Table>,
+
+    /// Terms and definitions of the open definition list
+    definition_list: Option<Vec<ParseNode>>,
+    /// Source of the open paragraph, which may be abbreviation definitions
+    paragraph_source: Option<String>,
+    /// First line of the open quote, which may be a callout marker
+    quote_line: Option<String>,
+    /// Whether the events are of a callout marker line, which is read from
+    /// the source instead
+    in_callout_marker: bool,
+}
+
+impl TreeBuilder {
+    fn event(&mut self, event: Event<'_>) {
+        if self.in_callout_marker {
+            if matches!(
+                event,
+                Event::SoftBreak | Event::HardBreak | Event::End(TagEnd::Paragraph)
+            ) {
+                self.in_callout_marker = false;
+            }
+            return;
+        }
+        match event {
+            Event::Start(tag) => self.start(tag),
+            Event::End(tag) => self.end(tag),
+            Event::Text(text) => self.text(&text),
+            Event::Code(code) => self.code(&code),
+            Event::InlineMath(text) | Event::DisplayMath(text) => self.text(&text),
+            Event::InlineHtml(html) => self.inline_html(&html),
+            Event::Html(html) => {
+                if let Some(buf) = &mut self.html {
+                    buf.push_str(&html);
+                } else {
+                    self.inline_html(&html);
+                }
+            }
+            Event::FootnoteReference(label) => {
+                let space = if self.space && !self.leaves.is_empty() {
+                    " "
+                } else {
+                    ""
+                };
+                self.leaves.push(ParseNode::new(
+                    MdParseEnum::FootnoteRef,
+                    format!("{space}{label}"),
+                ));
+                self.space = false;
+            }
+            Event::SoftBreak => {
+                if let Some((_, _, text)) = &mut self.link {
+                    text.push(' ');
+                } else {
+                    self.space = true;
+                }
+            }
+            Event::HardBreak => {
+                if let Some((_, _, text)) = &mut self.link {
+                    text.push(' ');
+                } else {
+                    self.leaves
+                        .push(ParseNode::new(MdParseEnum::HardBreak, String::new()));
+                    self.space = false;
+                }
+            }
+            Event::Rule => {
+                self.start_block(false);
+                self.emit(ParseNode::new(
+                    MdParseEnum::HorizontalSeparator,
+                    "---".to_string(),
+                ));
+            }
+            Event::TaskListMarker(checked) => {
+                if let Some(item) = &mut self.item {
+                    item.task = Some(checked);
+                }
+            }
+        }
+    }
+
+    fn start(&mut self, tag: Tag<'_>) {
+        match tag {
+            Tag::DefinitionList
+                if self.frames.is_empty() && self.item.is_none() && self.lists.is_empty() =>
+            {
+                self.flush_paragraph();
+                self.definition_list = Some(Vec::new());
+            }
+            Tag::Paragraph => self.start_block(true),
+            Tag::TableHead
+            | Tag::TableRow
+            | Tag::DefinitionList
+            | Tag::DefinitionListTitle
+            | Tag::DefinitionListDefinition
+            | Tag::MetadataBlock(_) => {}
+            Tag::Heading { level, id, .. } => {
+                self.start_block(false);
+                self.heading = Some((level as usize, id.map(|id| id.to_string())));
+            }
+            Tag::BlockQuote(kind) => {
+                self.start_block(false);
+                // pulldown-cmark only leaves out the line of GitHub alerts
+                let marker = self.quote_line.take().and_then(|line| {
+                    let line = line.trim_start().strip_prefix('>')?.trim();
+                    callouts::marker(line).map(|_| line.to_owned())
+                });
+                if marker.is_some() {
+                    self.in_callout_marker = kind.is_none();
+                }
+                self.open_frame(FrameKind::Quote(marker));
+            }
+            Tag::CodeBlock(kind) => {
+                let language = match kind {
+                    CodeBlockKind::Fenced(info) => info
+                        .split_whitespace()
+                        .next()
+                        .unwrap_or_default()
+                        .to_string(),
+                    CodeBlockKind::Indented => String::new(),
+                };
+                self.start_block(false);
+                self.code_block = Some((language, String::new()));
+            }
+            Tag::HtmlBlock => {
+                self.html = Some(String::new());
+            }
+            Tag::List(start) => {
+                if self.item_columns.is_empty() {
+                    self.start_block(false);
+                }
+                // A nested list ends the text of the item it belongs to
+                self.flush_item();
+                self.lists.push(start);
+            }
+            Tag::Item => {
+                self.flush_item();
+                self.item_columns.push(self.item_column.take().unwrap_or(2));
+                let depth = self.lists.len().saturating_sub(1);
+                let number = self.lists.last_mut().and_then(|next| {
+                    let number = *next;
+                    if let Some(n) = next {
+                        *n += 1;
+                    }
+                    number
+                });
+                self.item = Some(ListItem {
+                    depth,
+                    number,
+                    task: None,
+                    paragraph_done: false,
+                });
+            }
+            Tag::FootnoteDefinition(label) => {
+                self.footnote = Some(label.to_string());
+            }
+            Tag::Table(alignments) => {
+                self.start_block(false);
+                self.table = Some(Table {
+                    alignments,
+                    cells: Vec::new(),
+                });
+            }
+            Tag::TableCell => {
+                self.leaves.clear();
+                self.space = false;
+            }
+            Tag::Emphasis => self.italic += 1,
+            Tag::Strong => self.bold += 1,
+            Tag::Strikethrough => self.strikethrough += 1,
+            Tag::Subscript => self.subscript += 1,
+            Tag::Superscript => self.superscript += 1,
+            Tag::Link {
+                dest_url, title, ..
+            } => {
+                self.link = Some((dest_url.to_string(), title.to_string(), String::new()));
+            }
+            Tag::Image {
+                dest_url,
+                link_type,
+                ..
+            } => {
+                let embed = matches!(link_type, LinkType::WikiLink { .. });
+                self.image = Some((dest_url.to_string(), String::new(), embed));
+            }
+        }
+    }
+
+    fn end(&mut self, tag: TagEnd) {
+        match tag {
+            TagEnd::Paragraph => {
+                if let Some(item) = &mut self.item {
+                    item.paragraph_done = true;
+                    self.space = true;
+                } else if self.footnote.is_some()
+                    || self.definition_list.is_some()
+                    || self.in_quote()
+                {
+                    // Paragraphs of a quote are a single block until another
+                    // kind of block turns it into a container
+                    self.space = true;
+                } else {
+                    self.flush_paragraph();
+                }
+            }
+            TagEnd::Heading(_) => {
+                if let Some((level, id)) = self.heading.take() {
+                    let mut node =
+                        ParseNode::new(MdParseEnum::Heading, format!("{} ", "#".repeat(level)));
+                    node.add_children(self.take_leaves());
+                    if let Some(id) = id {
+                        node.add_children(vec![ParseNode::new(MdParseEnum::HeadingId, id)]);
+                    }
+                    self.emit(node);
+                }
+            }
+            TagEnd::BlockQuote(_) => {
+                let Some((FrameKind::Quote(marker), blocks)) = self.close_frame() else {
+                    return;
+                };
+                self.emit(quote(marker, blocks));
+            }
+            TagEnd::CodeBlock => {
+                let Some((language, code)) = self.code_block.take() else {
+                    return;
+                };
+
+                let mut node = ParseNode::new(MdParseEnum::CodeBlock, code.clone());
+                if !language.is_empty() {
+                    node.add_children(vec![ParseNode::new(MdParseEnum::PLanguage, language)]);
+                }
+                node.add_children(
+                    code.lines()
+                        .map(|line| {
+                            ParseNode::new(
+                                MdParseEnum::CodeBlockStr,
+                                format!("\n{}", line.replace('\t', "    ")),
+                            )
+                        })
+                        .collect(),
+                );
+                node.add_children(vec![ParseNode::new(
+                    MdParseEnum::CodeBlockStr,
+                    "\n".to_string(),
+                )]);
+                self.emit(node);
+            }
+            TagEnd::HtmlBlock => {
+                if let Some(html) = self.html.take() {
+                    self.html_block(&html);
+                }
+            }
+            TagEnd::List(_) => {
+                self.flush_item();
+                self.lists.pop();
+                if self.lists.is_empty() {
+                    self.flush_list();
+                }
+            }
+            TagEnd::Item => {
+                if self.lists.is_empty()
+                    && matches!(self.frames.last(), Some(frame) if matches!(frame.kind, FrameKind::Item(_)))
+                {
+                    // The item the frame holds the blocks of ends
+                    if let Some((FrameKind::Item(column), blocks)) = self.close_frame() {
+                        // Indented from the text of the item around it
+                        let base = match self.frames.last() {
+                            Some(Frame {
+                                kind: FrameKind::Item(base),
+                                ..
+                            }) => *base,
+                            _ => 0,
+                        };
+                        let indent = " ".repeat(column.saturating_sub(base));
+                        let mut node = ParseNode::new(MdParseEnum::ListItemContainer, indent);
+                        node.add_children(blocks);
+                        self.emit(node);
+                    }
+                } else {
+                    self.flush_item();
+                }
+                self.item_columns.pop();
+            }
+            TagEnd::FootnoteDefinition => {
+                if let Some(label) = self.footnote.take() {
+                    let mut node = ParseNode::new(MdParseEnum::Footnote, String::new());
+                    node.add_children(vec![ParseNode::new(MdParseEnum::FootnoteRef, label)]);
+                    node.add_children(self.take_leaves());
+                    self.emit(node);
+                }
+            }
+            TagEnd::TableCell => {
+                let mut cell = ParseNode::new(MdParseEnum::TableCell, String::new());
+                cell.add_children(self.take_leaves());
+                if let Some(table) = &mut self.table {
+                    table.cells.push(cell);
+                }
+            }
+            TagEnd::TableHead => {
+                if let Some(table) = &mut self.table {
+                    let separators = table.alignments.iter().map(|alignment| {
+                        let content = match alignment {
+                            Alignment::None => "---|",
+                            Alignment::Left => ":--|",
+                            Alignment::Center => ":-:|",
+                            Alignment::Right => "--:|",
+                        };
+                        ParseNode::new(MdParseEnum::TableSeparator, content.to_string())
+                    });
+                    table.cells.extend(separators);
+                }
+            }
+            TagEnd::Table => {
+                if let Some(table) = self.table.take() {
+                    let mut node = ParseNode::new(MdParseEnum::Table, String::new());
+                    node.add_children(table.cells);
+                    self.emit(node);
+                }
+            }
+            TagEnd::Emphasis => self.italic = self.italic.saturating_sub(1),
+            TagEnd::Strong => self.bold = self.bold.saturating_sub(1),
+            TagEnd::Strikethrough => self.strikethrough = self.strikethrough.saturating_sub(1),
+            TagEnd::Link => {
+                let Some((url, title, text)) = self.link.take() else {
+                    return;
+                };
+                let separator = if self.space && !self.leaves.is_empty() {
+                    " "
+                } else {
+                    ""
+                };
+                self.leaves
+                    .push(ParseNode::new(MdParseEnum::Word, separator.to_string()));
+                self.leaves.push(ParseNode::new(MdParseEnum::Link, text));
+                self.leaves.push(ParseNode::new(MdParseEnum::LinkData, url));
+                if !title.is_empty() {
+                    self.leaves
+                        .push(ParseNode::new(MdParseEnum::LinkTitle, title));
+                }
+                self.space = false;
+            }
+            TagEnd::Image => {
+                let Some((url, alt, embed)) = self.image.take() else {
+                    return;
+                };
+                let standalone = self.leaves.is_empty()
+                    && self.frames.is_empty()
+                    && self.lists.is_empty()
+                    && self.table.is_none()
+                    && self.heading.is_none()
+                    && self.footnote.is_none();
+                if standalone && embed {
+                    let mut node = ParseNode::new(MdParseEnum::Embed, String::new());
+                    node.add_children(vec![ParseNode::new(MdParseEnum::LinkData, url)]);
+                    self.images.push(node);
+                } else if standalone {
+                    let mut node = ParseNode::new(MdParseEnum::Image, String::new());
+                    node.add_children(vec![
+                        ParseNode::new(MdParseEnum::AltText, alt),
+                        ParseNode::new(MdParseEnum::LinkData, url),
+                    ]);
+                    self.images.push(node);
+                } else {
+                    self.text(&format!("[{alt}]"));
+                }
+            }
+            TagEnd::DefinitionListTitle | TagEnd::DefinitionListDefinition
+                if self.definition_list.is_some() =>
+            {
+                let kind = if tag == TagEnd::DefinitionListTitle {
+                    MdParseEnum::DefinitionTerm
+                } else {
+                    MdParseEnum::Definition
+                };
+                let mut node = ParseNode::new(kind, String::new());
+                node.add_children(self.take_leaves());
+                if let Some(list) = &mut self.definition_list {
+                    list.push(node);
+                }
+            }
+            TagEnd::DefinitionList if self.definition_list.is_some() => {
+                if let Some(children) = self.definition_list.take() {
+                    let mut node = ParseNode::new(MdParseEnum::DefinitionList, String::new());
+                    node.add_children(children);
+                    self.emit(node);
+                }
+            }
+            TagEnd::TableRow
+            | TagEnd::DefinitionList
+            | TagEnd::DefinitionListTitle
+            | TagEnd::DefinitionListDefinition
+            | TagEnd::MetadataBlock(_) => {}
+            TagEnd::Subscript => self.subscript = self.subscript.saturating_sub(1),
+            TagEnd::Superscript => self.superscript = self.superscript.saturating_sub(1),
+        }
+    }
+
+    fn text(&mut self, text: &str) {
+        if let Some((_, code)) = &mut self.code_block {
+            code.push_str(text);
+            return;
+        }
+        if let Some((_, _, link_text)) = &mut self.link {
+            link_text.push_str(text);
+            return;
+        }
+        if let Some((_, alt, _)) = &mut self.image {
+            alt.push_str(text);
+            return;
+        }
+
+        let kind = self.word_kind();
+        if text.starts_with(char::is_whitespace) {
+            self.space = true;
+        }
+        let ends_with_space = text.ends_with(char::is_whitespace);
+        let mut words = text.split_whitespace().peekable();
+        while let Some(word) = words.next() {
+            self.push_leaf(kind, word);
+            self.space = words.peek().is_some() || ends_with_space;
+        }
+    }
+
+    fn code(&mut self, code: &str) {
+        if let Some((_, _, link_text)) = &mut self.link {
+            link_text.push_str(code);
+            return;
+        }
+        self.push_leaf(MdParseEnum::Code, code);
+        self.space = false;
+    }
+
+    /// Tags become leaves of their own for `html` to resolve, like the ones the
+    /// grammar finds
+    fn inline_html(&mut self, html: &str) {
+        for (part, is_tag) in html::split_tags(html) {
+            if !is_tag {
+                self.text(part);
+            } else if self.link.is_none() && self.image.is_none() {
+                self.push_leaf(MdParseEnum::HtmlTag, part);
+                self.space = false;
+            }
+        }
+    }
+
+    fn push_leaf(&mut self, kind: MdParseEnum, word: &str) {
+        let script = match kind {
+            MdParseEnum::Subscript => html::subscript(word),
+            MdParseEnum::Superscript => html::superscript(word),
+            _ => None,
+        };
+        let word = script.as_deref().unwrap_or(word);
+        let content = if self.space && !self.leaves.is_empty() {
+            format!(" {word}")
+        } else {
+            word.to_string()
+        };
+        self.leaves.push(ParseNode::new(kind, content));
+    }
+
+    fn word_kind(&self) -> MdParseEnum {
+        if self.bold > 0 && self.italic > 0 {
+            MdParseEnum::BoldItalic
+        } else if self.bold > 0 {
+            MdParseEnum::Bold
+        } else if self.italic > 0 {
+            MdParseEnum::Italic
+        } else if self.strikethrough > 0 {
+            MdParseEnum::Strikethrough
+        } else if self.subscript > 0 {
+            MdParseEnum::Subscript
+        } else if self.superscript > 0 {
+            MdParseEnum::Superscript
+        } else {
+            MdParseEnum::Word
+        }
+    }
+
+    fn take_leaves(&mut self) -> Vec<ParseNode> {
+        self.space = false;
+        std::mem::take(&mut self.leaves)
+    }
+
+    fn emit(&mut self, node: ParseNode) {
+        if let Some(frame) = self.frames.last_mut() {
+            frame.blocks.push(node);
+        } else if let Some(details) = self.details.last_mut() {
+            details.body.push(node);
+        } else {
+            self.blocks.push(node);
+        }
+    }
+
+    fn flush_paragraph(&mut self) {
+        for image in std::mem::take(&mut self.images) {
+            self.emit(image);
+        }
+        if self.leaves.is_empty() {
+            return;
+        }
+        if let Some(definitions) = self.paragraph_source.take().and_then(|source| {
+            source
+                .lines()
+                .map(abbreviations::definition)
+                .collect::<Option<Vec<_>>>()
+        }) {
+            self.leaves.clear();
+            for definition in definitions {
+                self.emit(definition);
+            }
+            return;
+        }
+        let mut node = ParseNode::new(MdParseEnum::Paragraph, String::new());
+        node.add_children(self.take_leaves());
+        self.emit(node);
+    }
+
+    fn flush_item(&mut self) {
+        let Some(item) = self.item.take() else {
+            return;
+        };
+        let leaves = self.take_leaves();
+
+        if let Some(checked) = item.task {
+            // Tasks are their own block in the pest grammar
+            self.flush_list();
+            let marker = if checked { "- [x] " } else { "- [ ] " };
+            let kind = if checked {
+                MdParseEnum::TaskClosed
+            } else {
+                MdParseEnum::TaskOpen
+            };
+            let mut node = ParseNode::new(MdParseEnum::Task, String::new());
+            node.add_children(vec![ParseNode::new(kind, marker.to_string())]);
+            node.add_children(leaves);
+            self.emit(node);
+            return;
+        }
+
+        let indent = ParseNode::new(MdParseEnum::Indent, "  ".repeat(item.depth));
+        let mut node = if let Some(number) = item.number {
+            let mut node = ParseNode::new(MdParseEnum::OrderedList, String::new());
+            node.add_children(vec![
+                indent,
+                ParseNode::new(MdParseEnum::Digit, format!("{number}. ")),
+            ]);
+            node
+        } else {
+            let mut node = ParseNode::new(MdParseEnum::UnorderedList, String::new());
+            node.add_children(vec![indent]);
+            node
+        };
+        node.add_children(leaves);
+        self.list_items.push(node);
+    }
+
+    fn flush_list(&mut self) {
+        if self.list_items.is_empty() {
+            return;
+        }
+        let mut node = ParseNode::new(MdParseEnum::ListContainer, String::new());
+        node.add_children(std::mem::take(&mut self.list_items));
+        self.emit(node);
+    }
+
+    fn in_quote(&self) -> bool {
+        self.frames
+            .last()
+            .is_some_and(|frame| matches!(frame.kind, FrameKind::Quote(_)))
+    }
+
+    /// Called when a block starts. One after the first paragraph of a list
+    /// item goes in a container, which ends the list so far like in the pest
+    /// grammar. The items after it continue in a new list. In a quote, the
+    /// paragraph before the block becomes a block of its own.
+    fn start_block(&mut self, paragraph: bool) {
+        let after_text = match &self.item {
+            Some(item) => !paragraph || item.paragraph_done,
+            // After a nested list
+            None => !self.item_columns.is_empty(),
+        };
+        if after_text {
+            let column = self.item_columns.last().copied().unwrap_or_default();
+            self.flush_item();
+            self.flush_list();
+            self.open_frame(FrameKind::Item(column));
+        } else if self.in_quote() && self.item.is_none() {
+            self.flush_paragraph();
+        }
+    }
+
+    fn open_frame(&mut self, kind: FrameKind) {
+        self.frames.push(Frame {
+            kind,
+            blocks: Vec::new(),
+            lists: std::mem::take(&mut self.lists),
+            list_items: std::mem::take(&mut self.list_items),
+            item: self.item.take(),
+            item_columns: std::mem::take(&mut self.item_columns),
+        });
+    }
+
+    /// End the innermost frame and put the lists around it back
+    fn close_frame(&mut self) -> Option<(FrameKind, Vec<ParseNode>)> {
+        let quote_text = self.in_quote() && self.frames.last()?.blocks.is_empty();
+        if !quote_text {
+            self.flush_item();
+            self.flush_list();
+            self.flush_paragraph();
+        }
+        let frame = self.frames.pop()?;
+        self.lists = frame.lists;
+        self.list_items = frame.list_items;
+        self.item = frame.item;
+        self.item_columns = frame.item_columns;
+        if quote_text {
+            // A quote of only text stays a single block
+            let mut node = ParseNode::new(MdParseEnum::Paragraph, String::new());
+            node.add_children(self.take_leaves());
+            return Some((frame.kind, vec![node]));
+        }
+        Some((frame.kind, frame.blocks))
+    }
+
+    fn html_block(&mut self, html: &str) {
+        let trimmed = html.trim();
+        let lowercase = trimmed.to_lowercase();
+
+        if lowercase.starts_with("<!--") {
+            return;
+        }
+
+        self.start_block(false);
+        if lowercase.starts_with("<details") {
+            let tag_end = lowercase.find('>').unwrap_or(lowercase.len());
+            let open = lowercase[..tag_end].split_whitespace().any(|a| a == "open");
+            let summary = lowercase
+                .find("<summary>")
+                .zip(lowercase.find("</summary>"))
+                .filter(|(start, end)| start < end)
+                .map(|(start, end)| trimmed[start + "<summary>".len()..end].trim().to_string())
+                .unwrap_or_default();
+            self.details.push(Details {
+                open,
+                summary,
+                body: Vec::new(),
+            });
+            if !lowercase.contains("</details>") {
+                return;
+            }
+        }
+
+        if lowercase.contains("</details>") {
+            let Some(details) = self.details.pop() else {
+                return;
+            };
+            let mut node = ParseNode::new(MdParseEnum::Details, String::new());
+            if details.open {
+                node.add_children(vec![ParseNode::new(
+                    MdParseEnum::DetailsOpenAttr,
+                    "open".to_string(),
+                )]);
+            }
+            if !details.summary.is_empty() {
+                node.add_children(vec![ParseNode::new(
+                    MdParseEnum::DetailsSummary,
+                    details.summary,
+                )]);
+            }
+            let mut body = ParseNode::new(MdParseEnum::DetailsBody, String::new());
+            body.add_children(details.body);
+            node.add_children(vec![body]);
+            self.emit(node);
+            return;
+        }
+
+        self.inline_html(trimmed);
+        if self.item.is_none() && !self.in_quote() {
+            self.flush_paragraph();
+        }
+    }
+
+    fn finish(mut self) -> Vec<ParseNode> {
+        self.flush_item();
+        self.flush_list();
+        self.flush_paragraph();
+        while let Some(details) = self.details.pop() {
+            for node in details.body {
+                self.emit(node);
+            }
+        }
+        self.blocks
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        nodes::{textcomponent::TextNode, word::WordType},
+        parser::{ParserBackend, parse_markdown_with},
+    };
+
+    fn component_kinds(backend: ParserBackend, md: &str) -> Vec<TextNode> {
+        parse_markdown_with(backend, None, md, 80)
+            .components()
+            .iter()
+            .map(|c| c.kind())
+            .collect()
+    }
+
+    #[test]
+    fn same_blocks_as_pest() {
+        let md = "# Title\n\nSome *text* with a [link](https://example.com).\n\n\
+            - one\n- two\n  - nested\n\n\
+            ```rust\nfn main() {}\n```\n\n\
+            > quoted\n\n\
+            | a | b |\n|---|---|\n| 1 | 2 |\n\n\
+            ---\n\n\
+            - [ ] task\n";
+        assert_eq!(
+            component_kinds(ParserBackend::CommonMark, md),
+            component_kinds(ParserBackend::Pest, md)
+        );
+    }
+
+    #[test]
+    fn same_words_as_pest() {
+        let md = "Plain **bold** and *italic* ~~gone~~ `code` [link](https://example.com) end.\n";
+        let words = |backend| {
+            parse_markdown_with(backend, None, md, 80)
+                .words()
+                .into_iter()
+                .map(|w| (w.content().to_string(), w.kind()))
+                .collect::<Vec<_>>()
+        };
+        let commonmark = words(ParserBackend::CommonMark);
+        assert!(commonmark.contains(&("link".to_string(), WordType::Link)));
+        assert!(commonmark.contains(&("bold".to_string(), WordType::Bold)));
+        let text =
+            |words: &[(String, WordType)]| words.iter().map(|w| w.0.as_str()).collect::<String>();
+        assert_eq!(text(&commonmark), text(&words(ParserBackend::Pest)));
+    }
+
+    #[test]
+    fn extensions_match_pest() {
+        let md = "H~2~O and 2^10^ ==marked== :tada: at https://example.com\n";
+        let words = |backend| {
+            parse_markdown_with(backend, None, md, 80)
+                .words()
+                .into_iter()
+                .map(|w| (w.content().trim().to_string(), w.kind()))
+                .filter(|(w, _)| !w.is_empty())
+                .collect::<Vec<_>>()
+        };
+        let commonmark = words(ParserBackend::CommonMark);
+        assert!(commonmark.contains(&("₂".to_string(), WordType::Subscript)));
+        assert!(commonmark.contains(&("https://example.com".to_string(), WordType::Link)));
+        assert_eq!(commonmark, words(ParserBackend::Pest));
+    }
+
+    #[test]
+    fn definition_lists_and_abbreviations_match_pest() {
+        let md = "Intro to HTML.\n\nHTML\n: A *markup* language\n\n*[HTML]: Hyper Text Markup Language\n";
+        let lines = |backend| {
+            let root = parse_markdown_with(backend, None, md, 80);
+            assert_eq!(
+                root.abbreviation("HTML"),
+                Some("Hyper Text Markup Language")
+            );
+            root.content()
+        };
+        assert_eq!(lines(ParserBackend::CommonMark), lines(ParserBackend::Pest));
+    }
+
+    #[test]
+    fn footnotes_match_pest() {
+        let md = "A note[^n] and [^m].\n\n[^n]: With **bold**.\n\nText.\n\n[^m]: Another.\n";
+        let lines = |backend| {
+            parse_markdown_with(backend, None, md, 80)
+                .content()
+                .into_iter()
+                .filter(|l| !l.trim().is_empty())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(lines(ParserBackend::CommonMark), lines(ParserBackend::Pest));
+    }
+
+    #[test]
+    fn callouts_match_pest() {
+        let md = "> [!NOTE]\n> A note.\n\n> [!bug]- It crashes\n> On start.\n\n\
+            > [!tip] Title\n>\n> Body.\n";
+        let components = |backend| {
+            parse_markdown_with(backend, None, md, 80)
+                .components()
+                .into_iter()
+                .map(|c| (c.content_as_lines(), c.containers().to_vec(), c.is_hidden()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            components(ParserBackend::CommonMark),
+            components(ParserBackend::Pest)
+        );
+    }
+
+    #[test]
+    fn nested_blocks_match_pest() {
+        let md = "> quote\n>\n> ```\n> code\n> ```\n>\n> > nested\n\n\
+            1. first\n\n   second paragraph\n\n   - nested\n2. next\n   > quoted\n   > - item\n\n\
+            > - a\n> - b\n>\n> | x | y |\n> |---|---|\n> | 1 | 2 |\n";
+        let components = |backend| {
+            parse_markdown_with(backend, None, md, 80)
+                .components()
+                .into_iter()
+                .map(|c| (c.kind(), c.content_as_lines(), c.containers().to_vec()))
+                .collect::<Vec<_>>()
+        };
+        let commonmark = components(ParserBackend::CommonMark);
+        assert!(
+            commonmark
+                .iter()
+                .any(|(kind, _, containers)| *kind == TextNode::CodeBlock
+                    && !containers.is_empty())
+        );
+        assert_eq!(commonmark, components(ParserBackend::Pest));
+    }
+
+    #[test]
+    fn embeds_match_pest() {
+        let md = "Text.\n\n![[missing#Some heading]]\n\n![[missing.png|300]]\n";
+        let kinds = |backend| {
+            parse_markdown_with(backend, None, md, 80)
+                .components()
+                .into_iter()
+                .map(|c| (c.kind(), c.content_as_lines()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(kinds(ParserBackend::CommonMark), kinds(ParserBackend::Pest));
+    }
+
+    #[test]
+    fn hard_breaks_match_pest() {
+        let md = "John Doe  \n12 Main St.\\\nSpringfield\nUSA\n";
+        let height = |backend| {
+            parse_markdown_with(backend, None, md, 80)
+                .components()
+                .first()
+                .map(|c| c.height())
+        };
+        assert_eq!(height(ParserBackend::CommonMark), Some(3));
+        assert_eq!(
+            height(ParserBackend::CommonMark),
+            height(ParserBackend::Pest)
+        );
+    }
+
+    #[test]
+    fn details_blocks_fold() {
+        let md = "<details>\n<summary>More</summary>\n\nhidden body\n\n</details>\n";
+        let root = parse_markdown_with(ParserBackend::CommonMark, None, md, 80);
+        let summary = root
+            .components()
+            .into_iter()
+            .find(|c| matches!(c.kind(), TextNode::DetailsSummary { .. }))
+            .expect("expected a details summary");
+        assert_eq!(summary.content_as_lines(), vec!["More".to_string()]);
+        let body = root
+            .components()
+            .into_iter()
+            .find(|c| c.kind() == TextNode::Paragraph)
+            .expect("expected the details body");
+        assert!(body.is_hidden());
+    }
+}
@@ -0,0 +1,327 @@
+//! Line based pre-pass which finds list items and quotes that hold other
+//! blocks. The grammar only allows sentences inside those, so a code block in
+//! a list item or a list in a quote would end them. Their content is cut out
+//! here so it can be parsed on its own and nested in its container.
+
+use super::callouts;
+
+/// A run of lines of the document. `line` is the 0-based line it starts on.
+#[derive(Debug, PartialEq, Eq)]
+pub(super) enum Segment {
+    /// Lines the grammar can parse as they are
+    Text { line: usize, text: String },
+    /// The content of a quote with the `>` markers removed, and the callout
+    /// marker line if the quote starts with one
+    Quote {
+        line: usize,
+        callout: Option<String>,
+        text: String,
+    },
+    /// Blocks following the first paragraph of a list item, dedented to the
+    /// column the item's text starts at
+    ListItem {
+        line: usize,
+        indent: usize,
+        text: String,
+    },
+}
+
+pub(super) fn split(content: &str) -> Vec<Segment> {
+    let lines: Vec<&str> = content.split_inclusive('\n').collect();
+
+    let mut segments = Vec::new();
+    let mut text = String::new();
+    let mut text_line = 0;
+
+    // Columns the text of the open list items start at, innermost last
+    let mut items: Vec<usize> = Vec::new();
+    let mut fence: Option<&str> = None;
+    let mut details = 0usize;
+    let mut blank = false;
+
+    let mut i = 0;
+    while i < lines.len() {
+        let line = lines[i];
+        let trimmed = line.trim_start();
+        let indent = indent_width(line);
+
+        if let Some(marker) = fence {
+            if trimmed.starts_with(marker) {
+                fence = None;
+            }
+            text.push_str(line);
+            i += 1;
+            continue;
+        }
+
+        // `<details>` bodies are parsed by the grammar, leave them alone
+        let lowercase = trimmed.to_lowercase();
+        let opened = lowercase.matches("<details").count();
+        let closed = lowercase.matches("</details>").count();
+        if details > 0 || opened > 0 {
+            details = (details + opened).saturating_sub(closed);
+            text.push_str(line);
+            i += 1;
+            continue;
+        }
+
+        if trimmed.trim().is_empty() {
+            blank = true;
+            text.push_str(line);
+            i += 1;
+            continue;
+        }
+
+        // A line directly after a paragraph line continues it, even when it
+        // is indented less than the list item
+        let lazy = !blank && !starts_block(trimmed);
+        if !lazy {
+            while items.last().is_some_and(|column| *column > indent) {
+                items.pop();
+            }
+        }
+
+        if let Some(&column) = items.last()
+            && indent >= column
+            && (blank || starts_block(trimmed))
+            && list_marker(trimmed).is_none()
+        {
+            let end = item_end(&lines, i, column);
+            flush_text(&mut segments, &mut text, text_line);
+            segments.push(Segment::ListItem {
+                line: i,
+                indent: column,
+                text: lines[i..end].iter().map(|l| dedent(l, column)).collect(),
+            });
+            items.pop();
+            blank = false;
+            i = end;
+            text_line = end;
+            continue;
+        }
+
+        if let Some(width) = list_marker(trimmed) {
+            items.push(indent + width);
+        } else if trimmed.starts_with('>') && indent < 4 {
+            let end = quote_end(&lines, i);
+            let inner: Vec<&str> = lines[i..end].iter().map(|l| strip_quote(l)).collect();
+
+            if inner
+                .iter()
+                .map(|l| l.trim_start())
+                .any(|l| starts_block(l) || list_marker(l).is_some())
+            {
+                flush_text(&mut segments, &mut text, text_line);
+                let (callout, inner) = split_callout(&inner);
+                segments.push(Segment::Quote {
+                    line: i,
+                    callout,
+                    text: inner,
+                });
+                text_line = end;
+            } else {
+                lines[i..end].iter().for_each(|l| text.push_str(l));
+            }
+            items.clear();
+            blank = false;
+            i = end;
+            continue;
+        } else if trimmed.starts_with("```") {
+            fence = Some("```");
+        } else if trimmed.starts_with("~~~") {
+            fence = Some("~~~");
+        }
+
+        text.push_str(line);
+        blank = false;
+        i += 1;
+    }
+    flush_text(&mut segments, &mut text, text_line);
+    segments
+}
+
+fn flush_text(segments: &mut Vec<Segment>, text: &mut String, line: usize) {
+    if !text.is_empty() {
+        segments.push(Segment::Text {
+            line,
+            text: std::mem::take(text),
+        });
+    }
+}
+
+fn indent_width(line: &str) -> usize {
+    line.chars()
+        .take_while(|c| *c == ' ' || *c == '\t')
+        .map(|c| if c == '\t' { 4 } else { 1 })
+        .sum()
+}
+
+/// Remove up to `width` columns of indentation
+fn dedent(line: &str, width: usize) -> &str {
+    let mut removed = 0;
+    let mut start = 0;
+    for (i, c) in line.char_indices() {
+        if removed >= width || !(c == ' ' || c == '\t') {
+            break;
+        }
+        removed += if c == '\t' { 4 } else { 1 };
+        start = i + 1;
+    }
+    &line[start..]
+}
+
+fn strip_quote(line: &str) -> &str {
+    let line = line.trim_start().strip_prefix('>').unwrap_or(line);
+    line.strip_prefix([' ', '\t']).unwrap_or(line)
+}
+
+/// Width of the list marker a line starts with, including the spaces after
+/// it. Like in CommonMark, more than four spaces start an indented code block
+/// instead, so only the first one counts then.
+fn list_marker(line: &str) -> Option<usize> {
+    let marker = if line.starts_with("- ") || line.starts_with("* ") {
+        let rest = &line[1..].trim_start();
+        if ["[ ] ", "[x] ", "[X] "].iter().any(|t| rest.starts_with(t)) {
+            return None;
+        }
+        1
+    } else {
+        let digits = line.chars().take_while(char::is_ascii_digit).count();
+        if digits == 0 || !line[digits..].starts_with(". ") {
+            return None;
+        }
+        digits + 1
+    };
+
+    let spaces = line[marker..].chars().take_while(|c| *c == ' ').count();
+    Some(marker + if spaces > 4 { 1 } else { spaces })
+}
+
+/// The line after the last one of the quote starting at `start`. A text line
+/// right after a quoted one belongs to the quote, even without a `>`.
+fn quote_end(lines: &[&str], start: usize) -> usize {
+    let mut end = start + 1;
+    while end < lines.len() {
+        let line = lines[end].trim_start();
+        let lazy = !line.is_empty()
+            && !strip_quote(lines[end - 1]).trim().is_empty()
+            && !starts_block(line)
+            && list_marker(line).is_none();
+        if !line.starts_with('>') && !lazy {
+            break;
+        }
+        end += 1;
+    }
+    end
+}
+
+/// Whether a line starts a block the grammar doesn't allow inside a list item
+/// or quote
+fn starts_block(line: &str) -> bool {
+    let hashes = line.chars().take_while(|c| *c == '#').count();
+    let is_heading = (1..=6).contains(&hashes) && line[hashes..].starts_with(' ');
+    let is_rule = line.trim_end().len() >= 3 && line.trim_end().chars().all(|c| c == '-');
+
+    is_heading
+        || is_rule
+        || ["```", "~~~", ">", "|", "![", "- [ ] ", "- [x] ", "- [X] "]
+            .iter()
+            .any(|prefix| line.starts_with(prefix))
+}
+
+/// The line after the last one that belongs to the list item whose text
+/// starts at `column`. Blank lines at the end are left out.
+fn item_end(lines: &[&str], start: usize, column: usize) -> usize {
+    let mut end = start + 1;
+    for (i, line) in lines.iter().enumerate().skip(start + 1) {
+        if line.trim().is_empty() {
+            continue;
+        }
+        if indent_width(line) < column {
+            break;
+        }
+        end = i + 1;
+    }
+    end
+}
+
+fn split_callout(lines: &[&str]) -> (Option<String>, String) {
+    match lines.split_first() {
+        Some((first, rest)) if callouts::marker(first).is_some() => {
+            (Some(first.trim().to_string()), rest.concat())
+        }
+        _ => (None, lines.concat()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plain_lists_and_quotes_are_left_to_the_grammar() {
+        let md = "- one\n  - two\n- three\n\n> quote\n> [!NOTE]\n";
+        assert_eq!(
+            split(md),
+            vec![Segment::Text {
+                line: 0,
+                text: md.to_string()
+            }]
+        );
+    }
+
+    #[test]
+    fn code_block_in_list_item() {
+        let md = "1. Install\n\n   ```sh\n   cargo install md-tui\n   ```\n\n2. Run\n";
+        assert_eq!(
+            split(md),
+            vec![
+                Segment::Text {
+                    line: 0,
+                    text: "1. Install\n\n".to_string()
+                },
+                Segment::ListItem {
+                    line: 2,
+                    indent: 3,
+                    text: "```sh\ncargo install md-tui\n```\n".to_string()
+                },
+                Segment::Text {
+                    line: 5,
+                    text: "\n2. Run\n".to_string()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn nested_quote_and_callout() {
+        let md = "> [!TIP]\n> - a list\n>> nested\n";
+        assert_eq!(
+            split(md),
+            vec![Segment::Quote {
+                line: 0,
+                callout: Some("[!TIP]".to_string()),
+                text: "- a list\n> nested\n".to_string()
+            }]
+        );
+    }
+
+    #[test]
+    fn callout_title_is_kept_with_the_marker() {
+        let md = "> [!bug]- It crashes\n> - on start\n";
+        assert_eq!(
+            split(md),
+            vec![Segment::Quote {
+                line: 0,
+                callout: Some("[!bug]- It crashes".to_string()),
+                text: "- on start\n".to_string()
+            }]
+        );
+    }
+
+    #[test]
+    fn fenced_code_is_not_split() {
+        let md = "```\n> not a quote\n- item\n\n  text\n```\n";
+        assert_eq!(split(md).len(), 1);
+    }
+}
@@ -0,0 +1,418 @@
+//! Obsidian embeds, `![[note]]` on a line of its own. The note, or with
+//! `![[note#heading]]` only the section under that heading, is parsed like any
+//! other file and shown in a bordered block where the embed is. Embedding an
+//! image, `![[image.png]]`, shows the image. Notes are looked up next to the
+//! file they are embedded in first, then from where mdt was started.
+
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    fs::read_to_string,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    nodes::{
+        root::{Component, ComponentProps},
+        textcomponent::{Container, TextComponent, TextNode},
+        word::{Word, WordType},
+    },
+    search::heading_slug,
+};
+
+use super::{
+    MdParseEnum, ParseNode, ParserBackend, build_nodes, parse_component, parse_components,
+    references::normalize,
+};
+
+/// How deep embeds are followed into notes embedding other notes
+const MAX_DEPTH: usize = 3;
+
+const IMAGE_EXTENSIONS: [&str; 8] = ["png", "jpg", "jpeg", "gif", "bmp", "webp", "tif", "tiff"];
+
+/// A file whose nodes are being built
+struct Host {
+    file: Option<PathBuf>,
+    backend: ParserBackend,
+    link_titles: HashMap<String, String>,
+    abbreviations: HashMap<String, String>,
+}
+
+thread_local! {
+    /// The file being built and the files embedding it, outermost first
+    static HOSTS: RefCell<Vec<Host>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Pops the host when the nodes are built, also if building panics
+struct HostGuard;
+
+impl Drop for HostGuard {
+    fn drop(&mut self) {
+        HOSTS.with_borrow_mut(Vec::pop);
+    }
+}
+
+/// Build the nodes of `file` with `build`, so embeds in it are found
+/// relative to it. Returns the link titles and abbreviations of the notes
+/// embedded in it, which apply to their part of the document.
+pub(super) fn within<T>(
+    backend: ParserBackend,
+    file: Option<&str>,
+    build: impl FnOnce() -> T,
+) -> (T, HashMap<String, String>, HashMap<String, String>) {
+    HOSTS.with_borrow_mut(|hosts| {
+        hosts.push(Host {
+            file: file.map(PathBuf::from),
+            backend,
+            link_titles: HashMap::new(),
+            abbreviations: HashMap::new(),
+        });
+    });
+    let guard = HostGuard;
+    let built = build();
+    let (link_titles, abbreviations) = HOSTS.with_borrow_mut(|hosts| {
+        hosts.last_mut().map_or_else(Default::default, |host| {
+            (
+                std::mem::take(&mut host.link_titles),
+                std::mem::take(&mut host.abbreviations),
+            )
+        })
+    });
+    drop(guard);
+    (built, link_titles, abbreviations)
+}
+
+/// Put the notes and images that `nodes` embed in place of their embeds. A
+/// note becomes an embed node with the nodes of the note as its children.
+pub(super) fn expand(nodes: Vec<ParseNode>) -> Vec<ParseNode> {
+    nodes
+        .into_iter()
+        .map(|mut node| {
+            if node.kind() == MdParseEnum::Embed {
+                return expand_embed(&node);
+            }
+            let children = std::mem::take(&mut node.children);
+            node.children = expand(children);
+            node
+        })
+        .collect()
+}
+
+fn expand_embed(parse_node: &ParseNode) -> ParseNode {
+    let target = parse_node
+        .children()
+        .iter()
+        .find(|c| c.kind() == MdParseEnum::LinkData)
+        .map_or_else(String::new, |c| c.content().trim().to_owned());
+    let (name, heading) = match target.split_once('#') {
+        Some((name, heading)) => (name, Some(heading)),
+        None => (target.as_str(), None),
+    };
+
+    let is_image = Path::new(name)
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| IMAGE_EXTENSIONS.contains(&e.to_lowercase().as_str()));
+    if is_image {
+        let path = find(name).unwrap_or_else(|| PathBuf::from(name));
+        let mut image = ParseNode::new(MdParseEnum::Image, String::new());
+        image.add_children(vec![
+            ParseNode::new(MdParseEnum::AltText, name.to_owned()),
+            ParseNode::new(MdParseEnum::LinkData, path.to_string_lossy().into_owned()),
+        ]);
+        return image;
+    }
+
+    let file = if name.ends_with(".md") {
+        name.to_owned()
+    } else {
+        format!("{name}.md")
+    };
+    let body = match find(&file) {
+        None => Err(format!("Could not find {file}")),
+        Some(path) if is_host(&path) => Err(format!("Not embedded, {target} embeds itself")),
+        Some(_) if depth() >= MAX_DEPTH => {
+            Err(format!("Not embedded, {target} is nested too deep"))
+        }
+        Some(path) => embed(&path, heading),
+    };
+    let body = body.unwrap_or_else(|message| {
+        let mut paragraph = ParseNode::new(MdParseEnum::Paragraph, String::new());
+        paragraph.add_children(vec![ParseNode::new(MdParseEnum::Italic, message)]);
+        vec![paragraph]
+    });
+
+    let mut node = ParseNode::new(MdParseEnum::Embed, target);
+    node.add_children(body);
+    node
+}
+
+/// The components an embed put in by `expand` is shown as
+pub(super) fn components(parse_node: ParseNode) -> Vec<Component> {
+    let target = parse_node.content().to_owned();
+    let mut body: Vec<Component> = parse_node
+        .children_owned()
+        .into_iter()
+        .flat_map(parse_components)
+        .collect();
+    // The border is drawn right around the content
+    while body
+        .first()
+        .is_some_and(|c| c.kind() == TextNode::LineBreak)
+    {
+        body.remove(0);
+    }
+    while body.last().is_some_and(|c| c.kind() == TextNode::LineBreak) {
+        body.pop();
+    }
+
+    let mut components = vec![Component::TextComponent(TextComponent::new(
+        TextNode::EmbedTitle,
+        vec![Word::new(target, WordType::Normal)],
+    ))];
+    for mut component in body {
+        if let Component::TextComponent(tc) = &mut component {
+            tc.prepend_container(Container::Embed);
+        }
+        components.push(component);
+    }
+    components.push(Component::TextComponent(TextComponent::new(
+        TextNode::EmbedEnd,
+        Vec::new(),
+    )));
+    components
+}
+
+fn depth() -> usize {
+    HOSTS.with_borrow(|hosts| hosts.len().saturating_sub(1))
+}
+
+/// Whether `path` is the file being built or one embedding it, which would
+/// embed it again and again
+fn is_host(path: &Path) -> bool {
+    let Ok(path) = path.canonicalize() else {
+        return false;
+    };
+    HOSTS.with_borrow(|hosts| {
+        hosts
+            .iter()
+            .filter_map(|host| host.file.as_deref()?.canonicalize().ok())
+            .any(|file| file == path)
+    })
+}
+
+/// Look `name` up next to the file it is embedded in, then from the working
+/// directory
+fn find(name: &str) -> Option<PathBuf> {
+    let dir = HOSTS.with_borrow(|hosts| {
+        hosts
+            .last()
+            .and_then(|host| host.file.as_deref()?.parent().map(Path::to_path_buf))
+    });
+    dir.map(|dir| dir.join(name))
+        .into_iter()
+        .chain([PathBuf::from(name)])
+        .find(|path| path.is_file())
+}
+
+fn embed(path: &Path, heading: Option<&str>) -> Result<Vec<ParseNode>, String> {
+    let text =
+        read_to_string(path).map_err(|e| format!("Could not read {}: {e}", path.display()))?;
+    let backend = HOSTS.with_borrow(|hosts| hosts.last().map(|host| host.backend));
+    let parsed = build_nodes(
+        backend.unwrap_or_default(),
+        Some(&path.to_string_lossy()),
+        &text,
+    );
+
+    HOSTS.with_borrow_mut(|hosts| {
+        if let Some(host) = hosts.last_mut() {
+            host.link_titles.extend(parsed.link_titles);
+            host.abbreviations.extend(parsed.abbreviations);
+        }
+    });
+
+    let mut nodes = match heading {
+        Some(heading) => section(parsed.nodes, heading)
+            .ok_or_else(|| format!("Could not find heading {heading} in {}", path.display()))?,
+        None => parsed.nodes,
+    };
+    scope_footnotes(&mut nodes, &format!("{}#", path.display()));
+    Ok(nodes)
+}
+
+/// The heading `heading` links to and what comes after it, up to the next
+/// heading of the same or a higher level. The footnote definitions of the
+/// rest of the note are kept for the references in it.
+fn section(nodes: Vec<ParseNode>, heading: &str) -> Option<Vec<ParseNode>> {
+    let anchor = heading_slug(heading);
+    let start = nodes
+        .iter()
+        .position(|node| heading_of(node).is_some_and(|(_, a)| a == anchor || a == heading))?;
+    let level = heading_of(&nodes[start]).map(|(level, _)| level);
+    let end = nodes
+        .iter()
+        .skip(start + 1)
+        .position(|node| heading_of(node).is_some_and(|(l, _)| Some(l) <= level))
+        .map_or(nodes.len(), |i| start + 1 + i);
+
+    let mut section = Vec::new();
+    let mut definitions = Vec::new();
+    for (i, node) in nodes.into_iter().enumerate() {
+        if (start..end).contains(&i) {
+            section.push(node);
+        } else if node.kind() == MdParseEnum::Footnote {
+            definitions.push(node);
+        }
+    }
+    section.extend(definitions);
+    Some(section)
+}
+
+/// The level and anchor of a heading node
+fn heading_of(node: &ParseNode) -> Option<(u8, String)> {
+    if node.kind() != MdParseEnum::Heading {
+        return None;
+    }
+    match parse_component(node.clone()) {
+        Component::TextComponent(tc) => {
+            Some((tc.heading_level()?, tc.heading_anchor()?.to_owned()))
+        }
+        Component::Image(_) => None,
+    }
+}
+
+/// Put `prefix` in front of the footnote labels a note defines, so they
+/// don't mix with the ones of the note embedding it when they are numbered
+fn scope_footnotes(nodes: &mut [ParseNode], prefix: &str) {
+    fn defined(nodes: &[ParseNode], labels: &mut HashSet<String>) {
+        for node in nodes {
+            if node.kind() == MdParseEnum::Footnote
+                && let Some(label) = node
+                    .children()
+                    .first()
+                    .filter(|c| c.kind() == MdParseEnum::FootnoteRef)
+            {
+                labels.insert(normalize(label.content()));
+            }
+            defined(node.children(), labels);
+        }
+    }
+
+    fn rename(nodes: &mut [ParseNode], labels: &HashSet<String>, prefix: &str) {
+        for node in nodes {
+            if node.kind() == MdParseEnum::FootnoteRef {
+                let (space, label) = match node.content().strip_prefix(' ') {
+                    Some(label) => (" ", label),
+                    None => ("", node.content()),
+                };
+                if labels.contains(&normalize(label)) {
+                    node.content = format!("{space}{prefix}{label}");
+                }
+            }
+            rename(&mut node.children, labels, prefix);
+        }
+    }
+
+    let mut labels = HashSet::new();
+    defined(nodes, &mut labels);
+    rename(nodes, &labels, prefix);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use crate::{
+        nodes::textcomponent::{Container, TextNode},
+        parser::parse_markdown,
+    };
+
+    #[test]
+    fn notes_and_sections_are_embedded_in_a_border() {
+        let dir = std::env::temp_dir().join(format!("mdt-embeds-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("other.md"),
+            "# Other\n\nIntro.\n\n## Part\n\nIn the part.\n\n## Next\n\nNot in it.\n",
+        )
+        .unwrap();
+        // A chain of notes stops at the depth limit
+        for i in 1..6 {
+            fs::write(
+                dir.join(format!("deep{i}.md")),
+                format!("Level {i}\n\n![[deep{}]]\n", i + 1),
+            )
+            .unwrap();
+        }
+        let host = dir.join("host.md");
+        let md = "![[other#Part]]\n\n![[deep1]]\n\n![[missing]]\n";
+
+        let root = parse_markdown(host.to_str(), md, 80);
+        let lines = root.content();
+        fs::remove_dir_all(&dir).unwrap();
+
+        let text = lines.join("\n");
+        assert!(text.contains("## Part"));
+        assert!(text.contains("In the part."));
+        assert!(!text.contains("Intro."));
+        assert!(!text.contains("Not in it."));
+        assert!(text.contains("nested too deep"));
+        assert!(text.contains("Could not find missing.md"));
+
+        let components = root.components();
+        assert_eq!(components[0].kind(), TextNode::EmbedTitle);
+        let embedded = components
+            .iter()
+            .filter(|c| c.containers() == [Container::Embed])
+            .count();
+        assert!(embedded > 3);
+    }
+
+    #[test]
+    fn notes_embedding_each_other_stop_at_the_first_repeat() {
+        let dir = std::env::temp_dir().join(format!("mdt-embed-cycle-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("a.md"), "In a\n\n![[b]]\n").unwrap();
+        fs::write(dir.join("b.md"), "In b\n\n![[a]]\n").unwrap();
+        let a = dir.join("a.md");
+        let md = fs::read_to_string(&a).unwrap();
+
+        let root = parse_markdown(a.to_str(), &md, 80);
+        fs::remove_dir_all(&dir).unwrap();
+
+        let text = root.content().join("\n");
+        assert_eq!(text.matches("In a").count(), 1);
+        assert_eq!(text.matches("In b").count(), 1);
+        assert!(text.contains("Not embedded, a embeds itself"));
+    }
+
+    #[test]
+    fn footnotes_of_embedded_notes_are_numbered_with_the_document() {
+        let dir = std::env::temp_dir().join(format!("mdt-embed-notes-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("other.md"),
+            "# Other\n\n## Part\n\nEmbedded[^1].\n\n## Next\n\n[^1]: From other.\n",
+        )
+        .unwrap();
+        let host = dir.join("host.md");
+        let md = "Host[^1].\n\n![[other#Part]]\n\n[^1]: From host.\n";
+
+        let root = parse_markdown(host.to_str(), md, 80);
+        fs::remove_dir_all(&dir).unwrap();
+
+        let text = root.content().join("\n");
+        assert!(text.contains("Host[1]"));
+        assert!(text.contains("Embedded[2]"));
+        assert_eq!(text.matches("Footnotes").count(), 1);
+        assert_eq!(
+            root.footnote("[1]").map(|f| f.content_as_lines().concat()),
+            Some("1. From host.".to_owned())
+        );
+        assert_eq!(
+            root.footnote("[2]").map(|f| f.content_as_lines().concat()),
+            Some("2. From other.".to_owned())
+        );
+    }
+}
@@ -0,0 +1,109 @@
+//! Backslash escapes and HTML entities. The grammar keeps an escaped character
+//! from starting emphasis, a link or a table cell, the backslash is removed
+//! here together with decoding `&amp;`, `&#65;` and `&#x41;` style entities.
+
+use super::entities::ENTITIES;
+
+/// Decode the escapes and entities of a word. Entities that aren't known are
+/// kept as written.
+pub(super) fn unescape(text: &str) -> String {
+    if !text.contains(['\\', '&']) {
+        return text.to_owned();
+    }
+
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        match c {
+            '\\' => {
+                if let Some(escaped) = rest[1..].chars().next().filter(char::is_ascii_punctuation) {
+                    decoded.push(escaped);
+                    rest = &rest[1 + escaped.len_utf8()..];
+                    continue;
+                }
+            }
+            '&' => {
+                if let Some((entity, len)) = entity(rest) {
+                    decoded.push_str(&entity);
+                    rest = &rest[len..];
+                    continue;
+                }
+            }
+            _ => {}
+        }
+        decoded.push(c);
+        rest = &rest[c.len_utf8()..];
+    }
+    decoded
+}
+
+/// The entity at the start of the text and its length
+fn entity(text: &str) -> Option<(String, usize)> {
+    let end = text.find(';')?;
+    let name = &text[1..end];
+
+    let decoded = if let Some(number) = name.strip_prefix('#') {
+        let code = match number.strip_prefix(['x', 'X']) {
+            Some(hex)
+                if (1..=6).contains(&hex.len()) && hex.chars().all(|c| c.is_ascii_hexdigit()) =>
+            {
+                u32::from_str_radix(hex, 16).ok()?
+            }
+            None if (1..=7).contains(&number.len())
+                && number.chars().all(|c| c.is_ascii_digit()) =>
+            {
+                number.parse().ok()?
+            }
+            _ => return None,
+        };
+        // Like browsers, invalid code points become the replacement character
+        let c = char::from_u32(code)
+            .filter(|c| *c != '\0')
+            .unwrap_or(char::REPLACEMENT_CHARACTER);
+        c.to_string()
+    } else {
+        if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric()) {
+            return None;
+        }
+        named_entity(name)?.to_owned()
+    };
+    Some((decoded, end + 1))
+}
+
+/// What a named entity like `copy` decodes to
+fn named_entity(name: &str) -> Option<&'static str> {
+    ENTITIES
+        .binary_search_by(|(entity, _)| (*entity).cmp(name))
+        .ok()
+        .map(|i| ENTITIES[i].1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ENTITIES, unescape};
+
+    #[test]
+    fn escaped_punctuation_loses_the_backslash() {
+        assert_eq!(unescape(r"\*not\* \_x\_ \# \[a\]"), "*not* _x_ # [a]");
+        assert_eq!(unescape(r"a\\b \q"), r"a\b \q");
+    }
+
+    #[test]
+    fn entities_are_decoded() {
+        assert_eq!(unescape("&amp; &copy; &#x2192; &#65;"), "& © → A");
+        assert_eq!(unescape("&nbsp;"), "\u{a0}");
+        assert_eq!(unescape("a &ShortRightArrow; b"), "a → b");
+        assert_eq!(unescape("&nparsl;"), "\u{2afd}\u{20e5}");
+        assert_eq!(unescape("&copy &COPY;"), "&copy ©");
+        assert_eq!(
+            unescape("&bogus; & &#xZZ; &#0;"),
+            "&bogus; & &#xZZ; \u{fffd}"
+        );
+    }
+
+    #[test]
+    fn entities_are_sorted_for_binary_search() {
+        assert!(ENTITIES.len() > 2000);
+        assert!(ENTITIES.windows(2).all(|pair| pair[0].0 < pair[1].0));
+    }
+}
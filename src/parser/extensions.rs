@@ -0,0 +1,774 @@
+//! Extensions found in plain text after parsing: bare URLs and email
+//! addresses become links, `==text==` is marked, `~text~` and `^text^` are
+//! sub- and superscript, `:name:` is an emoji and `#name` is a tag. They only
+//! apply inside a run of plain words, so code and links keep them as written.
+
+use crate::search::tag_len;
+
+use super::{MdParseEnum, ParseNode, html};
+
+/// A piece of a run of words, either plain text or an extension
+#[derive(Debug, PartialEq, Eq)]
+enum Segment {
+    Text(String),
+    Styled(MdParseEnum, String),
+    Link { text: String, url: String },
+}
+
+pub(super) fn resolve(nodes: Vec<ParseNode>) -> Vec<ParseNode> {
+    nodes.into_iter().map(resolve_node).collect()
+}
+
+fn resolve_node(mut node: ParseNode) -> ParseNode {
+    if node.children().is_empty() {
+        return node;
+    }
+
+    let children = std::mem::take(&mut node.children);
+    let mut resolved = Vec::with_capacity(children.len());
+    let mut run: Vec<ParseNode> = Vec::new();
+    for child in children {
+        if is_plain_word(&child) {
+            run.push(child);
+        } else {
+            resolved.extend(resolve_run(std::mem::take(&mut run)));
+            resolved.push(resolve_node(child));
+        }
+    }
+    resolved.extend(resolve_run(run));
+    node.children = resolved;
+    node
+}
+
+fn is_plain_word(node: &ParseNode) -> bool {
+    node.kind() == MdParseEnum::Word && node.children().is_empty()
+}
+
+fn resolve_run(run: Vec<ParseNode>) -> Vec<ParseNode> {
+    let text: String = run.iter().map(ParseNode::content).collect();
+    if !text.contains(['=', '~', '^', ':', '@', '.', '#']) {
+        return run;
+    }
+    let segments = segments(&text);
+    if let [Segment::Text(_)] | [] = segments.as_slice() {
+        return run;
+    }
+
+    let mut nodes = Vec::new();
+    for segment in segments {
+        match segment {
+            Segment::Text(text) => nodes.extend(words(MdParseEnum::Word, &text)),
+            // The space in front of a tag isn't part of what is selected
+            Segment::Styled(MdParseEnum::Tag, text) => {
+                let (space, tag) = split_space(&text);
+                if !space.is_empty() {
+                    nodes.push(ParseNode::new(MdParseEnum::Word, space.to_owned()));
+                }
+                nodes.push(ParseNode::new(MdParseEnum::Tag, tag.to_owned()));
+            }
+            Segment::Styled(kind, text) => nodes.extend(words(kind, &text)),
+            Segment::Link { text, url } => {
+                let (space, text) = split_space(&text);
+                let mut node = ParseNode::new(MdParseEnum::Link, format!("{space}{text}"));
+                node.add_children(vec![
+                    ParseNode::new(MdParseEnum::Link, text.to_owned()),
+                    ParseNode::new(MdParseEnum::LinkData, url),
+                ]);
+                nodes.push(node);
+            }
+        }
+    }
+    nodes
+}
+
+/// One node per word, with a single space in front of the words that had
+/// whitespace before them
+fn words(kind: MdParseEnum, text: &str) -> Vec<ParseNode> {
+    let mut nodes = Vec::new();
+    let mut rest = text;
+    while !rest.is_empty() {
+        let trimmed = rest.trim_start();
+        let space = if trimmed.len() < rest.len() { " " } else { "" };
+        let end = trimmed.find(char::is_whitespace).unwrap_or(trimmed.len());
+        if end == 0 {
+            break;
+        }
+        nodes.push(ParseNode::new(kind, format!("{space}{}", &trimmed[..end])));
+        rest = &trimmed[end..];
+    }
+    nodes
+}
+
+fn split_space(text: &str) -> (&str, &str) {
+    match text.strip_prefix(char::is_whitespace) {
+        Some(rest) => (" ", rest.trim_start()),
+        None => ("", text),
+    }
+}
+
+fn segments(text: &str) -> Vec<Segment> {
+    let mut segments = Vec::new();
+    let mut plain = String::new();
+    let mut i = 0;
+    while let Some(c) = text[i..].chars().next() {
+        let before = text[..i].chars().next_back();
+        let found = autolink(text, i, before)
+            .or_else(|| delimited(text, i, before))
+            .or_else(|| emoji(text, i))
+            .or_else(|| tag(text, i, before));
+        if let Some((segment, len)) = found {
+            // Whitespace in front of an extension goes with it, so its first
+            // word keeps the space
+            let space = if plain.ends_with(char::is_whitespace) {
+                plain.truncate(plain.trim_end().len());
+                " "
+            } else {
+                ""
+            };
+            if !plain.is_empty() {
+                segments.push(Segment::Text(std::mem::take(&mut plain)));
+            }
+            segments.push(match segment {
+                Segment::Text(text) => Segment::Text(format!("{space}{text}")),
+                Segment::Styled(kind, text) => Segment::Styled(kind, format!("{space}{text}")),
+                Segment::Link { text, url } => Segment::Link {
+                    text: format!("{space}{text}"),
+                    url,
+                },
+            });
+            i += len;
+        } else {
+            plain.push(c);
+            i += c.len_utf8();
+        }
+    }
+    if !plain.is_empty() {
+        segments.push(Segment::Text(plain));
+    }
+    segments
+}
+
+/// A bare `https://`, `http://` or `www.` URL, or an email address, like
+/// GitHub links them
+fn autolink(text: &str, i: usize, before: Option<char>) -> Option<(Segment, usize)> {
+    if before.is_some_and(|c| !(c.is_whitespace() || "(*_~\"'".contains(c))) {
+        return None;
+    }
+    let rest = &text[i..];
+    let token = &rest[..rest
+        .find(|c: char| c.is_whitespace() || c == '<')
+        .unwrap_or(rest.len())];
+
+    let prefix = ["https://", "http://", "www."]
+        .into_iter()
+        .find(|prefix| token.to_ascii_lowercase().starts_with(prefix));
+    if let Some(prefix) = prefix {
+        let url = trim_url(token);
+        // Trimming can leave less than the prefix, "www." at the end of a
+        // sentence
+        let host = url.get(prefix.len()..).unwrap_or_default();
+        if host.is_empty() || (prefix == "www." && !host.contains('.')) {
+            return None;
+        }
+        let target = if prefix == "www." {
+            format!("http://{url}")
+        } else {
+            url.to_owned()
+        };
+        let segment = Segment::Link {
+            text: url.to_owned(),
+            url: target,
+        };
+        return Some((segment, url.len()));
+    }
+
+    let email = email(token)?;
+    let segment = Segment::Link {
+        text: email.to_owned(),
+        url: format!("mailto:{email}"),
+    };
+    Some((segment, email.len()))
+}
+
+/// Leave out punctuation ending the sentence rather than the URL, and a
+/// closing parenthesis that wasn't opened in it
+fn trim_url(url: &str) -> &str {
+    let mut url = url;
+    loop {
+        let trimmed =
+            url.trim_end_matches(['?', '!', '.', ',', ':', ';', '*', '_', '~', '\'', '"']);
+        let trimmed = if trimmed.ends_with(')')
+            && trimmed.matches(')').count() > trimmed.matches('(').count()
+        {
+            &trimmed[..trimmed.len() - 1]
+        } else {
+            trimmed
+        };
+        if trimmed.len() == url.len() {
+            return url;
+        }
+        url = trimmed;
+    }
+}
+
+fn email(token: &str) -> Option<&str> {
+    let at = token.find('@')?;
+    let local = &token[..at];
+    if local.is_empty()
+        || !local
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || ".+-_".contains(c))
+    {
+        return None;
+    }
+    let domain_len = token[at + 1..]
+        .find(|c: char| !(c.is_ascii_alphanumeric() || "-_.".contains(c)))
+        .unwrap_or(token.len() - at - 1);
+    let domain = token[at + 1..at + 1 + domain_len].trim_end_matches('.');
+    if !domain.contains('.') || domain.ends_with(['-', '_']) || domain.starts_with('.') {
+        return None;
+    }
+    Some(&token[..at + 1 + domain.len()])
+}
+
+/// `==mark==`, `~sub~` and `^sup^`. Marked text can't start or end with
+/// whitespace, sub- and superscripts can't have any, which keeps `~/path`
+/// and `a ~ b` as written.
+fn delimited(text: &str, i: usize, before: Option<char>) -> Option<(Segment, usize)> {
+    let rest = &text[i..];
+    let (delimiter, kind) = if rest.starts_with("==") {
+        ("==", MdParseEnum::Mark)
+    } else if rest.starts_with('~') {
+        ("~", MdParseEnum::Subscript)
+    } else if rest.starts_with('^') {
+        ("^", MdParseEnum::Superscript)
+    } else {
+        return None;
+    };
+    let first = delimiter.chars().next()?;
+    if before == Some(first) {
+        return None;
+    }
+
+    let inner = &rest[delimiter.len()..];
+    if inner.starts_with(char::is_whitespace) || inner.starts_with(first) {
+        return None;
+    }
+    let end = if kind == MdParseEnum::Mark {
+        inner.find(delimiter)?
+    } else {
+        let end = inner.find(|c: char| c.is_whitespace() || c == first)?;
+        if !inner[end..].starts_with(first) {
+            return None;
+        }
+        end
+    };
+    let content = &inner[..end];
+    let after = inner[end + delimiter.len()..].chars().next();
+    if content.is_empty() || content.ends_with(char::is_whitespace) || after == Some(first) {
+        return None;
+    }
+
+    let content = match kind {
+        MdParseEnum::Subscript => html::subscript(content).unwrap_or_else(|| content.to_owned()),
+        MdParseEnum::Superscript => {
+            html::superscript(content).unwrap_or_else(|| content.to_owned())
+        }
+        _ => content.to_owned(),
+    };
+    Some((Segment::Styled(kind, content), delimiter.len() * 2 + end))
+}
+
+/// A `:shortcode:` of a known emoji
+fn emoji(text: &str, i: usize) -> Option<(Segment, usize)> {
+    let rest = text[i..].strip_prefix(':')?;
+    let end = rest.find(|c: char| !(c.is_ascii_alphanumeric() || "_+-".contains(c)))?;
+    if !rest[end..].starts_with(':') {
+        return None;
+    }
+    let emoji = shortcode(&rest[..end])?;
+    Some((
+        Segment::Styled(MdParseEnum::Emoji, emoji.to_owned()),
+        end + 2,
+    ))
+}
+
+/// An Obsidian `#tag` at the start of a word
+fn tag(text: &str, i: usize, before: Option<char>) -> Option<(Segment, usize)> {
+    if before.is_some_and(|c| !c.is_whitespace()) {
+        return None;
+    }
+    let len = tag_len(&text[i..])?;
+    Some((
+        Segment::Styled(MdParseEnum::Tag, text[i..i + len].to_owned()),
+        len,
+    ))
+}
+
+/// The shortcodes GitHub users reach for most
+fn shortcode(name: &str) -> Option<&'static str> {
+    Some(match name {
+        "smile" => "😄",
+        "smiley" => "😃",
+        "grinning" => "😀",
+        "grin" => "😁",
+        "laughing" | "satisfied" => "😆",
+        "joy" => "😂",
+        "rofl" => "🤣",
+        "slightly_smiling_face" => "🙂",
+        "upside_down_face" => "🙃",
+        "wink" => "😉",
+        "blush" => "😊",
+        "innocent" => "😇",
+        "heart_eyes" => "😍",
+        "kissing_heart" => "😘",
+        "yum" => "😋",
+        "stuck_out_tongue" => "😛",
+        "sunglasses" => "😎",
+        "nerd_face" => "🤓",
+        "thinking" => "🤔",
+        "neutral_face" => "😐",
+        "expressionless" => "😑",
+        "no_mouth" => "😶",
+        "smirk" => "😏",
+        "unamused" => "😒",
+        "roll_eyes" => "🙄",
+        "grimacing" => "😬",
+        "relieved" => "😌",
+        "pensive" => "😔",
+        "sleepy" => "😪",
+        "sleeping" => "😴",
+        "mask" => "😷",
+        "dizzy_face" => "😵",
+        "exploding_head" => "🤯",
+        "confused" => "😕",
+        "worried" => "😟",
+        "frowning_face" => "☹️",
+        "open_mouth" => "😮",
+        "astonished" => "😲",
+        "flushed" => "😳",
+        "pleading_face" => "🥺",
+        "cry" => "😢",
+        "sob" => "😭",
+        "scream" => "😱",
+        "confounded" => "😖",
+        "disappointed" => "😞",
+        "sweat" => "😓",
+        "sweat_smile" => "😅",
+        "weary" => "😩",
+        "tired_face" => "😫",
+        "triumph" => "😤",
+        "rage" => "😡",
+        "angry" => "😠",
+        "skull" => "💀",
+        "poop" | "hankey" => "💩",
+        "clown_face" => "🤡",
+        "ghost" => "👻",
+        "alien" => "👽",
+        "robot" => "🤖",
+        "smiley_cat" => "😺",
+        "see_no_evil" => "🙈",
+        "wave" => "👋",
+        "ok_hand" => "👌",
+        "v" => "✌️",
+        "crossed_fingers" => "🤞",
+        "point_left" => "👈",
+        "point_right" => "👉",
+        "point_up" => "☝️",
+        "point_down" => "👇",
+        "+1" | "thumbsup" => "👍",
+        "-1" | "thumbsdown" => "👎",
+        "fist" => "✊",
+        "clap" => "👏",
+        "raised_hands" => "🙌",
+        "pray" => "🙏",
+        "handshake" => "🤝",
+        "muscle" => "💪",
+        "eyes" => "👀",
+        "brain" => "🧠",
+        "heart" => "❤️",
+        "orange_heart" => "🧡",
+        "yellow_heart" => "💛",
+        "green_heart" => "💚",
+        "blue_heart" => "💙",
+        "purple_heart" => "💜",
+        "black_heart" => "🖤",
+        "broken_heart" => "💔",
+        "sparkling_heart" => "💖",
+        "100" => "💯",
+        "boom" | "collision" => "💥",
+        "dizzy" => "💫",
+        "zzz" => "💤",
+        "speech_balloon" => "💬",
+        "sparkles" => "✨",
+        "star" => "⭐",
+        "star2" => "🌟",
+        "fire" => "🔥",
+        "zap" => "⚡",
+        "sunny" => "☀️",
+        "cloud" => "☁️",
+        "umbrella" => "☔",
+        "snowflake" => "❄️",
+        "rainbow" => "🌈",
+        "ocean" => "🌊",
+        "earth_americas" => "🌎",
+        "globe_with_meridians" => "🌐",
+        "crescent_moon" => "🌙",
+        "seedling" => "🌱",
+        "evergreen_tree" => "🌲",
+        "cactus" => "🌵",
+        "four_leaf_clover" => "🍀",
+        "rose" => "🌹",
+        "sunflower" => "🌻",
+        "cherry_blossom" => "🌸",
+        "bug" => "🐛",
+        "bee" | "honeybee" => "🐝",
+        "snail" => "🐌",
+        "turtle" => "🐢",
+        "snake" => "🐍",
+        "crab" => "🦀",
+        "octopus" => "🐙",
+        "whale" => "🐳",
+        "penguin" => "🐧",
+        "owl" => "🦉",
+        "unicorn" => "🦄",
+        "cat" => "🐱",
+        "dog" => "🐶",
+        "fox_face" => "🦊",
+        "panda_face" => "🐼",
+        "monkey" => "🐒",
+        "apple" => "🍎",
+        "banana" => "🍌",
+        "lemon" => "🍋",
+        "pizza" => "🍕",
+        "hamburger" => "🍔",
+        "taco" => "🌮",
+        "cake" => "🍰",
+        "cookie" => "🍪",
+        "coffee" => "☕",
+        "tea" => "🍵",
+        "beer" => "🍺",
+        "beers" => "🍻",
+        "wine_glass" => "🍷",
+        "tada" => "🎉",
+        "confetti_ball" => "🎊",
+        "balloon" => "🎈",
+        "gift" => "🎁",
+        "trophy" => "🏆",
+        "medal_sports" => "🏅",
+        "1st_place_medal" => "🥇",
+        "dart" => "🎯",
+        "game_die" => "🎲",
+        "video_game" => "🎮",
+        "art" => "🎨",
+        "musical_note" => "🎵",
+        "notes" => "🎶",
+        "rocket" => "🚀",
+        "airplane" => "✈️",
+        "car" => "🚗",
+        "bike" => "🚲",
+        "ship" => "🚢",
+        "construction" => "🚧",
+        "rotating_light" => "🚨",
+        "vertical_traffic_light" => "🚦",
+        "checkered_flag" => "🏁",
+        "triangular_flag_on_post" => "🚩",
+        "house" => "🏠",
+        "office" => "🏢",
+        "hourglass" => "⌛",
+        "watch" => "⌚",
+        "alarm_clock" => "⏰",
+        "stopwatch" => "⏱️",
+        "calendar" => "📆",
+        "date" => "📅",
+        "memo" | "pencil" => "📝",
+        "pencil2" => "✏️",
+        "book" | "open_book" => "📖",
+        "books" => "📚",
+        "bookmark" => "🔖",
+        "notebook" => "📓",
+        "page_facing_up" => "📄",
+        "clipboard" => "📋",
+        "pushpin" => "📌",
+        "round_pushpin" => "📍",
+        "paperclip" => "📎",
+        "scissors" => "✂️",
+        "file_folder" => "📁",
+        "open_file_folder" => "📂",
+        "card_index_dividers" => "🗂️",
+        "chart_with_upwards_trend" => "📈",
+        "chart_with_downwards_trend" => "📉",
+        "bar_chart" => "📊",
+        "package" => "📦",
+        "mailbox" => "📫",
+        "email" | "e-mail" => "📧",
+        "envelope" => "✉️",
+        "inbox_tray" => "📥",
+        "outbox_tray" => "📤",
+        "link" => "🔗",
+        "lock" => "🔒",
+        "unlock" => "🔓",
+        "key" => "🔑",
+        "closed_lock_with_key" => "🔐",
+        "shield" => "🛡️",
+        "hammer" => "🔨",
+        "wrench" => "🔧",
+        "hammer_and_wrench" => "🛠️",
+        "gear" => "⚙️",
+        "nut_and_bolt" => "🔩",
+        "toolbox" => "🧰",
+        "magnet" => "🧲",
+        "test_tube" => "🧪",
+        "microscope" => "🔬",
+        "telescope" => "🔭",
+        "satellite" => "📡",
+        "bulb" => "💡",
+        "flashlight" => "🔦",
+        "battery" => "🔋",
+        "electric_plug" => "🔌",
+        "computer" => "💻",
+        "desktop_computer" => "🖥️",
+        "keyboard" => "⌨️",
+        "printer" => "🖨️",
+        "floppy_disk" => "💾",
+        "cd" => "💿",
+        "iphone" => "📱",
+        "phone" | "telephone" => "☎️",
+        "camera" => "📷",
+        "movie_camera" => "🎥",
+        "tv" => "📺",
+        "radio" => "📻",
+        "loudspeaker" => "📢",
+        "mega" => "📣",
+        "bell" => "🔔",
+        "no_bell" => "🔕",
+        "mag" => "🔍",
+        "mag_right" => "🔎",
+        "moneybag" => "💰",
+        "dollar" => "💵",
+        "credit_card" => "💳",
+        "gem" => "💎",
+        "scales" | "balance_scale" => "⚖️",
+        "pill" => "💊",
+        "syringe" => "💉",
+        "dna" => "🧬",
+        "recycle" => "♻️",
+        "white_check_mark" => "✅",
+        "heavy_check_mark" => "✔️",
+        "ballot_box_with_check" => "☑️",
+        "x" => "❌",
+        "negative_squared_cross_mark" => "❎",
+        "heavy_multiplication_x" => "✖️",
+        "heavy_plus_sign" => "➕",
+        "heavy_minus_sign" => "➖",
+        "question" => "❓",
+        "grey_question" => "❔",
+        "exclamation" | "heavy_exclamation_mark" => "❗",
+        "grey_exclamation" => "❕",
+        "bangbang" => "‼️",
+        "interrobang" => "⁉️",
+        "warning" => "⚠️",
+        "no_entry" => "⛔",
+        "no_entry_sign" => "🚫",
+        "stop_sign" => "🛑",
+        "information_source" => "ℹ️",
+        "new" => "🆕",
+        "free" => "🆓",
+        "up" => "🆙",
+        "cool" => "🆒",
+        "ok" => "🆗",
+        "sos" => "🆘",
+        "red_circle" => "🔴",
+        "orange_circle" => "🟠",
+        "yellow_circle" => "🟡",
+        "green_circle" => "🟢",
+        "large_blue_circle" | "blue_circle" => "🔵",
+        "purple_circle" => "🟣",
+        "black_circle" => "⚫",
+        "white_circle" => "⚪",
+        "red_square" => "🟥",
+        "green_square" => "🟩",
+        "arrow_up" => "⬆️",
+        "arrow_down" => "⬇️",
+        "arrow_left" => "⬅️",
+        "arrow_right" => "➡️",
+        "arrow_forward" => "▶️",
+        "arrow_backward" => "◀️",
+        "arrows_counterclockwise" => "🔄",
+        "repeat" => "🔁",
+        "leftwards_arrow_with_hook" => "↩️",
+        "arrow_right_hook" => "↪️",
+        "heavy_dollar_sign" => "💲",
+        "copyright" => "©️",
+        "registered" => "®️",
+        "tm" => "™️",
+        "hash" => "#️⃣",
+        "zero" => "0️⃣",
+        "one" => "1️⃣",
+        "two" => "2️⃣",
+        "three" => "3️⃣",
+        "four" => "4️⃣",
+        "five" => "5️⃣",
+        "six" => "6️⃣",
+        "seven" => "7️⃣",
+        "eight" => "8️⃣",
+        "nine" => "9️⃣",
+        "keycap_ten" => "🔟",
+        "wastebasket" => "🗑️",
+        "lipstick" => "💄",
+        "crown" => "👑",
+        "tophat" => "🎩",
+        "mortar_board" => "🎓",
+        "eyeglasses" => "👓",
+        "necktie" => "👔",
+        "shirt" | "tshirt" => "👕",
+        "jeans" => "👖",
+        "running_shoe" => "👟",
+        "runner" | "running" => "🏃",
+        "walking" => "🚶",
+        "dancer" => "💃",
+        "man_technologist" => "👨‍💻",
+        "woman_technologist" => "👩‍💻",
+        "technologist" => "🧑‍💻",
+        "construction_worker" => "👷",
+        "detective" => "🕵️",
+        "ninja" => "🥷",
+        "baby" => "👶",
+        "family" => "👪",
+        "busts_in_silhouette" => "👥",
+        "bust_in_silhouette" => "👤",
+        "speaking_head" => "🗣️",
+        "hourglass_flowing_sand" => "⏳",
+        "stars" => "🌠",
+        "milky_way" => "🌌",
+        "volcano" => "🌋",
+        "mountain" => "⛰️",
+        "camping" => "🏕️",
+        "beach_umbrella" => "🏖️",
+        "desert_island" => "🏝️",
+        "world_map" => "🗺️",
+        "compass" => "🧭",
+        "anchor" => "⚓",
+        "bomb" => "💣",
+        "dagger" => "🗡️",
+        "crossed_swords" => "⚔️",
+        "bow_and_arrow" => "🏹",
+        "crystal_ball" => "🔮",
+        "magic_wand" => "🪄",
+        "jigsaw" => "🧩",
+        "teddy_bear" => "🧸",
+        "thread" => "🧵",
+        "label" => "🏷️",
+        "ticket" => "🎫",
+        "sparkler" => "🎇",
+        "fireworks" => "🎆",
+        "christmas_tree" => "🎄",
+        "jack_o_lantern" => "🎃",
+        "egg" => "🥚",
+        "bread" => "🍞",
+        "cheese" => "🧀",
+        "popcorn" => "🍿",
+        "doughnut" => "🍩",
+        "chocolate_bar" => "🍫",
+        "candy" => "🍬",
+        "lollipop" => "🍭",
+        "ice_cream" => "🍨",
+        "birthday" => "🎂",
+        "cocktail" => "🍸",
+        "tropical_drink" => "🍹",
+        "champagne" => "🍾",
+        "cup_with_straw" => "🥤",
+        "avocado" => "🥑",
+        "carrot" => "🥕",
+        "corn" => "🌽",
+        "hot_pepper" => "🌶️",
+        "mushroom" => "🍄",
+        "peach" => "🍑",
+        "cherries" => "🍒",
+        "strawberry" => "🍓",
+        "grapes" => "🍇",
+        "watermelon" => "🍉",
+        "pineapple" => "🍍",
+        "coconut" => "🥥",
+        "kiwi_fruit" => "🥝",
+        "tomato" => "🍅",
+        "eggplant" => "🍆",
+        "potato" => "🥔",
+        "salt" => "🧂",
+        "hocho" | "knife" => "🔪",
+        "spoon" => "🥄",
+        "fork_and_knife" => "🍴",
+        "plate_with_cutlery" => "🍽️",
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{nodes::word::WordType, parser::parse_markdown};
+
+    fn words(md: &str) -> Vec<(String, WordType)> {
+        parse_markdown(None, md, 80)
+            .components()
+            .into_iter()
+            .flat_map(|c| c.content().iter().flatten().chain(c.meta_info()))
+            .map(|w| (w.content().to_owned(), w.kind()))
+            .collect()
+    }
+
+    #[test]
+    fn bare_urls_and_emails_are_links() {
+        let words =
+            words("See https://example.com/a_(b). or mail me@example.org, www.rust-lang.org\n");
+        for (text, url) in [
+            ("https://example.com/a_(b)", "https://example.com/a_(b)"),
+            ("me@example.org", "mailto:me@example.org"),
+            ("www.rust-lang.org", "http://www.rust-lang.org"),
+        ] {
+            assert!(words.contains(&(text.to_owned(), WordType::Link)), "{text}");
+            assert!(
+                words.contains(&(url.to_owned(), WordType::LinkData)),
+                "{url}"
+            );
+        }
+    }
+
+    #[test]
+    fn incomplete_urls_are_text() {
+        for md in ["Visit www. now\n", "https://\n", "See https://. or www.\n"] {
+            let words = words(md);
+            assert!(!words.iter().any(|(_, t)| *t == WordType::Link), "{md}");
+        }
+    }
+
+    #[test]
+    fn marks_scripts_and_emoji_get_their_own_kinds() {
+        let words = words("==very important== H~2~O, 2^10^ :rocket: :not_an_emoji:\n");
+        assert!(words.contains(&("very".to_owned(), WordType::Mark)));
+        assert!(words.contains(&("important".to_owned(), WordType::Mark)));
+        assert!(words.contains(&("₂".to_owned(), WordType::Subscript)));
+        assert!(words.contains(&("¹⁰".to_owned(), WordType::Superscript)));
+        assert!(words.contains(&("🚀".to_owned(), WordType::Emoji)));
+        assert!(words.iter().any(|(w, _)| w.contains(":not_an_emoji:")));
+        assert!(!words.iter().any(|(w, _)| w.contains("==")));
+    }
+
+    #[test]
+    fn tags_are_words_of_their_own() {
+        let words = words("#rust and #cli/tools, not #1, a#b or `#code`\n");
+        assert!(words.contains(&("#rust".to_owned(), WordType::Tag)));
+        assert!(words.contains(&("#cli/tools".to_owned(), WordType::Tag)));
+        // Shown and kept as a target to select
+        assert_eq!(words.iter().filter(|(_, t)| *t == WordType::Tag).count(), 4);
+    }
+
+    #[test]
+    fn lookalikes_are_kept_as_written() {
+        let text: String = words("cd ~/src ~ a == b, at 12:30:45 see `==x==`\n")
+            .into_iter()
+            .map(|(w, _)| w)
+            .collect();
+        assert!(text.contains("~/src ~ a == b, at 12:30:45"));
+        assert!(text.contains("==x=="));
+    }
+}
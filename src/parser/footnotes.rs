@@ -0,0 +1,196 @@
+//! Footnotes, the way GitHub shows them. References are numbered in the order
+//! they first appear, and the definitions are moved to a Footnotes section at
+//! the end of the document in that order. References to a label that is never
+//! defined are kept as the text they were written as, and definitions that are
+//! never referenced are left out.
+
+use std::collections::{HashMap, HashSet};
+
+use itertools::Itertools;
+
+use super::{MdParseEnum, ParseNode, references::normalize};
+
+/// Number the footnote references and move the definitions they point to to
+/// the end
+pub(super) fn resolve(nodes: Vec<ParseNode>) -> Vec<ParseNode> {
+    let mut definitions = HashMap::new();
+    let nodes = take_definitions(nodes, &mut definitions);
+    let defined: HashSet<String> = definitions.keys().cloned().collect();
+
+    let mut order = Vec::new();
+    let mut nodes = number_references(nodes, &mut order, &defined);
+
+    // Footnotes can reference other footnotes, which are numbered after
+    let mut footnotes = Vec::new();
+    let mut i = 0;
+    while let Some(label) = order.get(i).cloned() {
+        i += 1;
+        let Some(mut definition) = definitions.remove(&label) else {
+            continue;
+        };
+        let children = std::mem::take(&mut definition.children);
+        let mut children = children.into_iter();
+        // The label of the definition becomes its number
+        children.next();
+        let mut numbered = vec![ParseNode::new(MdParseEnum::FootnoteRef, footnote_key(i))];
+        numbered.extend(number_references(children.collect(), &mut order, &defined));
+        definition.children = numbered;
+        footnotes.push(definition);
+    }
+
+    if footnotes.is_empty() {
+        return nodes;
+    }
+
+    let mut heading = ParseNode::new(MdParseEnum::Heading, "## Footnotes".to_owned());
+    heading.add_children(vec![ParseNode::new(
+        MdParseEnum::Word,
+        "Footnotes".to_owned(),
+    )]);
+
+    if nodes
+        .last()
+        .is_some_and(|n| n.kind() != MdParseEnum::BlockSeparator)
+    {
+        nodes.push(ParseNode::new(MdParseEnum::BlockSeparator, "\n".to_owned()));
+    }
+    nodes.push(ParseNode::new(
+        MdParseEnum::HorizontalSeparator,
+        "---".to_owned(),
+    ));
+    nodes.push(heading);
+    nodes.extend(footnotes);
+    nodes.push(ParseNode::new(MdParseEnum::BlockSeparator, "\n".to_owned()));
+    nodes
+}
+
+/// What a reference to the `number`th footnote shows, and what it is looked up
+/// by
+fn footnote_key(number: usize) -> String {
+    format!("[{number}]")
+}
+
+fn take_definitions(
+    nodes: Vec<ParseNode>,
+    definitions: &mut HashMap<String, ParseNode>,
+) -> Vec<ParseNode> {
+    let mut kept = Vec::with_capacity(nodes.len());
+    for mut node in nodes {
+        if node.kind() == MdParseEnum::Footnote {
+            let label = node
+                .children()
+                .first()
+                .filter(|c| c.kind() == MdParseEnum::FootnoteRef)
+                .map(|c| normalize(c.content()));
+            // The first definition of a label wins
+            if let Some(label) = label {
+                definitions.entry(label).or_insert(node);
+            }
+            continue;
+        }
+        let children = std::mem::take(&mut node.children);
+        node.children = take_definitions(children, definitions);
+        kept.push(node);
+    }
+
+    // Moved definitions leave the blank lines around them behind
+    kept.into_iter()
+        .dedup_by(|a, b| {
+            a.kind() == MdParseEnum::BlockSeparator && b.kind() == MdParseEnum::BlockSeparator
+        })
+        .collect()
+}
+
+fn number_references(
+    nodes: Vec<ParseNode>,
+    order: &mut Vec<String>,
+    defined: &HashSet<String>,
+) -> Vec<ParseNode> {
+    let mut numbered = Vec::with_capacity(nodes.len());
+    for mut node in nodes {
+        if node.kind() != MdParseEnum::FootnoteRef {
+            let children = std::mem::take(&mut node.children);
+            node.children = number_references(children, order, defined);
+            numbered.push(node);
+            continue;
+        }
+
+        // The label keeps the space in front of the reference
+        let (space, label) = match node.content().strip_prefix(' ') {
+            Some(label) => (" ", label),
+            None => ("", node.content()),
+        };
+        if !defined.contains(&normalize(label)) {
+            numbered.push(ParseNode::new(
+                MdParseEnum::Word,
+                format!("{space}[^{label}]"),
+            ));
+            continue;
+        }
+        if !space.is_empty() {
+            numbered.push(ParseNode::new(MdParseEnum::Word, space.to_owned()));
+        }
+        let label = normalize(label);
+        let number = match order.iter().position(|l| *l == label) {
+            Some(index) => index + 1,
+            None => {
+                order.push(label);
+                order.len()
+            }
+        };
+        numbered.push(ParseNode::new(
+            MdParseEnum::FootnoteRef,
+            footnote_key(number),
+        ));
+    }
+    numbered
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        nodes::{textcomponent::TextNode, word::WordType},
+        parser::parse_markdown,
+    };
+
+    const MD: &str = "See[^b] and [^a], not [^x].\n\n[^a]: The **first**.\n\n\
+        Middle [^B].\n\n[^b]: Second.\n\n[^unused]: Left out.\n\nEnd.\n";
+
+    #[test]
+    fn footnotes_are_numbered_and_moved_to_the_end() {
+        let root = parse_markdown(None, MD, 80);
+        let lines: Vec<_> = root
+            .content()
+            .into_iter()
+            .filter(|l| !l.trim().is_empty())
+            .collect();
+        assert_eq!(
+            lines,
+            [
+                "See[1] and [2], not [^x].",
+                "Middle [1].",
+                "End.",
+                "## Footnotes",
+                "1. Second.",
+                "2. The first.",
+            ]
+        );
+    }
+
+    #[test]
+    fn footnotes_keep_their_formatting_and_can_be_found() {
+        let mut root = parse_markdown(None, MD, 80);
+        root.set_scroll(0);
+        let footnote = root.footnote("[2]").expect("the second footnote");
+        assert_eq!(footnote.kind(), TextNode::Footnote);
+        assert!(
+            footnote
+                .content()
+                .iter()
+                .flatten()
+                .any(|w| w.kind() == WordType::Bold && w.content() == "first")
+        );
+        assert!(root.footnote_offset("[1]").unwrap() > 4);
+        assert!(root.footnote_offset("[3]").is_err());
+    }
+}
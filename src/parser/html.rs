@@ -0,0 +1,546 @@
+//! The inline HTML commonly found in READMEs. The grammar only finds the tags,
+//! here they are turned into words: formatting tags like `<kbd>` or `<sup>`
+//! change the kind of the words up to their closing tag, `<a href>` becomes a
+//! link, `<br>` a line break, and a paragraph of nothing but `<img>` tags the
+//! images. Other tags are dropped and their text is kept.
+
+use itertools::Itertools;
+
+use super::{MdParseEnum, ParseNode};
+
+/// A tag like `<a href="url">` or `</kbd>`
+#[derive(Debug, PartialEq, Eq)]
+pub(super) struct Tag {
+    name: String,
+    closing: bool,
+    attributes: Vec<(String, String)>,
+}
+
+impl Tag {
+    /// Parse a tag with any whitespace before it
+    pub(super) fn parse(raw: &str) -> Option<Self> {
+        let raw = raw.trim_start().strip_prefix('<')?.strip_suffix('>')?;
+        let (closing, raw) = match raw.strip_prefix('/') {
+            Some(raw) => (true, raw),
+            None => (false, raw),
+        };
+        let raw = match raw.strip_suffix('/') {
+            Some(_) if closing => return None,
+            Some(raw) => raw,
+            None => raw,
+        };
+
+        let name_len = raw
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '-'))
+            .unwrap_or(raw.len());
+        let name = raw[..name_len].to_ascii_lowercase();
+        if !name.starts_with(|c: char| c.is_ascii_alphabetic()) {
+            return None;
+        }
+
+        let mut attributes = Vec::new();
+        let mut rest = raw[name_len..].trim_start();
+        while !rest.is_empty() {
+            let key_len = rest
+                .find(|c: char| c.is_whitespace() || c == '=')
+                .unwrap_or(rest.len());
+            let key = rest[..key_len].to_ascii_lowercase();
+            rest = rest[key_len..].trim_start();
+
+            let mut value = String::new();
+            if let Some(after) = rest.strip_prefix('=') {
+                let after = after.trim_start();
+                let (quoted, end) = match after.chars().next() {
+                    Some(quote @ ('"' | '\'')) => {
+                        let end = after[1..].find(quote).map_or(after.len(), |i| i + 1);
+                        (after[1..end].to_owned(), (end + 1).min(after.len()))
+                    }
+                    _ => {
+                        let end = after.find(char::is_whitespace).unwrap_or(after.len());
+                        (after[..end].to_owned(), end)
+                    }
+                };
+                value = quoted;
+                rest = after[end..].trim_start();
+            }
+            if key.is_empty() {
+                break;
+            }
+            attributes.push((key, value));
+        }
+
+        // Closing tags have no attributes
+        if closing && !attributes.is_empty() {
+            return None;
+        }
+
+        Some(Self {
+            name,
+            closing,
+            attributes,
+        })
+    }
+
+    fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// The kind the words inside a formatting tag get
+    fn style(&self) -> Option<MdParseEnum> {
+        match self.name.as_str() {
+            "b" | "strong" => Some(MdParseEnum::Bold),
+            "i" | "em" => Some(MdParseEnum::Italic),
+            "s" | "del" | "strike" => Some(MdParseEnum::Strikethrough),
+            "code" => Some(MdParseEnum::Code),
+            "kbd" => Some(MdParseEnum::Kbd),
+            "sub" => Some(MdParseEnum::Subscript),
+            "sup" => Some(MdParseEnum::Superscript),
+            _ => None,
+        }
+    }
+}
+
+/// Split text into its HTML tags and the text between them, for parsers that
+/// hand over HTML as is
+#[cfg(feature = "commonmark")]
+pub(super) fn split_tags(text: &str) -> Vec<(&str, bool)> {
+    let mut parts = Vec::new();
+    let mut start = 0;
+    let mut search = 0;
+    while let Some(open) = text[search..].find('<').map(|i| i + search) {
+        let Some(close) = text[open..].find('>').map(|i| i + open + 1) else {
+            break;
+        };
+        if Tag::parse(&text[open..close]).is_some() && !text[open + 1..close].contains('<') {
+            if start < open {
+                parts.push((&text[start..open], false));
+            }
+            parts.push((&text[open..close], true));
+            start = close;
+            search = close;
+        } else {
+            search = open + 1;
+        }
+    }
+    if start < text.len() {
+        parts.push((&text[start..], false));
+    }
+    parts
+}
+
+pub(super) fn resolve(nodes: Vec<ParseNode>) -> Vec<ParseNode> {
+    let is_separator = |node: &ParseNode| node.kind() == MdParseEnum::BlockSeparator;
+    // Dropped paragraphs leave the blank lines around them behind
+    nodes
+        .into_iter()
+        .flat_map(resolve_node)
+        .skip_while(is_separator)
+        .dedup_by(|a, b| is_separator(a) && is_separator(b))
+        .collect()
+}
+
+fn resolve_node(mut node: ParseNode) -> Vec<ParseNode> {
+    if node.children().is_empty() {
+        return vec![node];
+    }
+
+    let children = std::mem::take(&mut node.children);
+    node.children = resolve_children(children);
+
+    // A paragraph made only of hidden tags, like a `<div>` line, has nothing to show
+    if node.kind() == MdParseEnum::Paragraph && node.children().is_empty() {
+        return Vec::new();
+    }
+
+    if node.kind() == MdParseEnum::Paragraph
+        && let Some(images) = standalone_images(node.children())
+    {
+        return images;
+    }
+
+    for child in &mut node.children {
+        if let Some(tag) = image_tag(child) {
+            let alt = tag.attribute("alt").unwrap_or_default();
+            let space = if child.content().starts_with(char::is_whitespace) {
+                " "
+            } else {
+                ""
+            };
+            *child = ParseNode::new(MdParseEnum::Word, format!("{space}[{alt}]"));
+        }
+    }
+    vec![node]
+}
+
+fn image_tag(node: &ParseNode) -> Option<Tag> {
+    if node.kind() != MdParseEnum::HtmlTag {
+        return None;
+    }
+    Tag::parse(node.content()).filter(|tag| tag.name == "img" && !tag.closing)
+}
+
+/// The images of a paragraph with nothing else in it, like the centered logo
+/// at the top of many READMEs
+fn standalone_images(children: &[ParseNode]) -> Option<Vec<ParseNode>> {
+    let mut images = Vec::new();
+    for child in children {
+        if let Some(tag) = image_tag(child) {
+            let mut image = ParseNode::new(MdParseEnum::Image, child.content().trim().to_owned());
+            image.add_children(vec![
+                ParseNode::new(
+                    MdParseEnum::AltText,
+                    tag.attribute("alt").unwrap_or_default().to_owned(),
+                ),
+                ParseNode::new(
+                    MdParseEnum::LinkData,
+                    tag.attribute("src").unwrap_or_default().to_owned(),
+                ),
+            ]);
+            images.push(image);
+        } else if !(child.content().trim().is_empty() || child.kind() == MdParseEnum::HardBreak) {
+            return None;
+        }
+    }
+    (!images.is_empty()).then_some(images)
+}
+
+/// An `<a href>` whose closing tag hasn't been seen yet
+struct OpenLink {
+    href: String,
+    start: usize,
+    space: bool,
+}
+
+#[derive(Default)]
+struct Inline {
+    resolved: Vec<ParseNode>,
+    /// Open formatting tags and where their words start
+    styles: Vec<(MdParseEnum, usize)>,
+    link: Option<OpenLink>,
+    /// A dropped tag had whitespace before it, which the next word gets
+    space: bool,
+}
+
+fn resolve_children(nodes: Vec<ParseNode>) -> Vec<ParseNode> {
+    if !nodes.iter().any(|n| n.kind() == MdParseEnum::HtmlTag) {
+        return nodes.into_iter().flat_map(resolve_node).collect();
+    }
+
+    let mut inline = Inline::default();
+    for node in nodes {
+        match node.kind() {
+            MdParseEnum::HtmlTag => inline.tag(node),
+            _ if node.children().is_empty() => inline.push(node),
+            _ => resolve_node(node).into_iter().for_each(|n| inline.push(n)),
+        }
+    }
+    inline.resolved
+}
+
+impl Inline {
+    fn push(&mut self, mut node: ParseNode) {
+        if self.space && !node.content().starts_with(' ') && !self.resolved.is_empty() {
+            node.content.insert(0, ' ');
+        }
+        self.space = false;
+
+        if node.kind() == MdParseEnum::Word
+            && let Some(&(style, _)) = self.styles.last()
+        {
+            node.kind = style;
+            let (space, text) = split_space(node.content());
+            let text = match style {
+                MdParseEnum::Superscript => superscript(text).unwrap_or_else(|| text.to_owned()),
+                MdParseEnum::Subscript => subscript(text).unwrap_or_else(|| text.to_owned()),
+                _ => text.to_owned(),
+            };
+            node.content = format!("{space}{text}");
+        }
+        self.resolved.push(node);
+    }
+
+    fn tag(&mut self, node: ParseNode) {
+        let Some(tag) = Tag::parse(node.content()) else {
+            return;
+        };
+        let space = node.content().starts_with(char::is_whitespace);
+
+        if let Some(style) = tag.style() {
+            self.space |= space;
+            if !tag.closing {
+                self.styles.push((style, self.resolved.len()));
+            } else if let Some(index) = self.styles.iter().rposition(|(s, _)| *s == style) {
+                let (_, start) = self.styles.remove(index);
+                if style == MdParseEnum::Kbd {
+                    self.key_cap(start);
+                }
+            }
+            return;
+        }
+
+        match (tag.name.as_str(), tag.closing) {
+            ("br", _) => {
+                self.resolved
+                    .push(ParseNode::new(MdParseEnum::HardBreak, String::new()));
+                self.space = false;
+            }
+            ("img", false) if self.link.is_none() => {
+                self.resolved.push(node);
+            }
+            ("img", false) => {
+                // Only the description of an image fits in the text of a link
+                let alt = tag.attribute("alt").unwrap_or_default();
+                self.push(ParseNode::new(MdParseEnum::Word, alt.to_owned()));
+            }
+            ("a", false) => {
+                if let Some(href) = tag.attribute("href") {
+                    self.link = Some(OpenLink {
+                        href: href.to_owned(),
+                        start: self.resolved.len(),
+                        space: space || self.space,
+                    });
+                    self.space = false;
+                } else {
+                    self.space |= space;
+                }
+            }
+            ("a", true) => {
+                if let Some(link) = self.link.take() {
+                    self.close_link(link);
+                }
+            }
+            _ => self.space |= space,
+        }
+    }
+
+    fn close_link(&mut self, link: OpenLink) {
+        let text: String = self
+            .resolved
+            .drain(link.start..)
+            .flat_map(leaf_nodes)
+            .map(|n| n.content)
+            .collect();
+        let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+        let text = if text.is_empty() {
+            link.href.clone()
+        } else {
+            text
+        };
+
+        let space = if link.space && !self.resolved.is_empty() {
+            " "
+        } else {
+            ""
+        };
+        let mut node = ParseNode::new(MdParseEnum::Link, format!("{space}{text}"));
+        node.add_children(vec![
+            ParseNode::new(MdParseEnum::Link, text),
+            ParseNode::new(MdParseEnum::LinkData, link.href),
+        ]);
+        self.resolved.push(node);
+    }
+
+    /// Pad the words of a `<kbd>` so they look like a key. The space before
+    /// the key is kept out of it.
+    fn key_cap(&mut self, start: usize) {
+        let keys: Vec<usize> = (start..self.resolved.len())
+            .filter(|i| self.resolved[*i].kind() == MdParseEnum::Kbd)
+            .collect();
+        let (Some(&first), Some(&last)) = (keys.first(), keys.last()) else {
+            return;
+        };
+
+        self.resolved[last].content.push(' ');
+        let (space, text) = split_space(self.resolved[first].content());
+        let (spaced, padded) = (!space.is_empty(), format!(" {text}"));
+        if spaced {
+            self.resolved
+                .insert(first, ParseNode::new(MdParseEnum::Word, " ".to_owned()));
+        }
+        let first = if spaced { first + 1 } else { first };
+        self.resolved[first].content = padded;
+    }
+}
+
+fn split_space(text: &str) -> (&str, &str) {
+    let start = text.len() - text.trim_start().len();
+    text.split_at(start)
+}
+
+fn leaf_nodes(node: ParseNode) -> Vec<ParseNode> {
+    if node.children().is_empty() {
+        if node.kind() == MdParseEnum::HardBreak {
+            return vec![ParseNode::new(MdParseEnum::Word, " ".to_owned())];
+        }
+        return vec![node];
+    }
+    node.children
+        .into_iter()
+        .filter(|n| n.kind() != MdParseEnum::LinkData)
+        .flat_map(leaf_nodes)
+        .collect()
+}
+
+/// The text in Unicode superscript, if every character has one
+pub(super) fn superscript(text: &str) -> Option<String> {
+    text.chars()
+        .map(|c| {
+            Some(match c {
+                '0' => '⁰',
+                '1' => '¹',
+                '2' => '²',
+                '3' => '³',
+                '4' => '⁴',
+                '5' => '⁵',
+                '6' => '⁶',
+                '7' => '⁷',
+                '8' => '⁸',
+                '9' => '⁹',
+                '+' => '⁺',
+                '-' => '⁻',
+                '=' => '⁼',
+                '(' => '⁽',
+                ')' => '⁾',
+                'a' => 'ᵃ',
+                'b' => 'ᵇ',
+                'c' => 'ᶜ',
+                'd' => 'ᵈ',
+                'e' => 'ᵉ',
+                'f' => 'ᶠ',
+                'g' => 'ᵍ',
+                'h' => 'ʰ',
+                'i' => 'ⁱ',
+                'j' => 'ʲ',
+                'k' => 'ᵏ',
+                'l' => 'ˡ',
+                'm' => 'ᵐ',
+                'n' => 'ⁿ',
+                'o' => 'ᵒ',
+                'p' => 'ᵖ',
+                'r' => 'ʳ',
+                's' => 'ˢ',
+                't' => 'ᵗ',
+                'u' => 'ᵘ',
+                'v' => 'ᵛ',
+                'w' => 'ʷ',
+                'x' => 'ˣ',
+                'y' => 'ʸ',
+                'z' => 'ᶻ',
+                ' ' => ' ',
+                _ => return None,
+            })
+        })
+        .collect()
+}
+
+/// The text in Unicode subscript, if every character has one
+pub(super) fn subscript(text: &str) -> Option<String> {
+    text.chars()
+        .map(|c| {
+            Some(match c {
+                '0' => '₀',
+                '1' => '₁',
+                '2' => '₂',
+                '3' => '₃',
+                '4' => '₄',
+                '5' => '₅',
+                '6' => '₆',
+                '7' => '₇',
+                '8' => '₈',
+                '9' => '₉',
+                '+' => '₊',
+                '-' => '₋',
+                '=' => '₌',
+                '(' => '₍',
+                ')' => '₎',
+                'a' => 'ₐ',
+                'e' => 'ₑ',
+                'h' => 'ₕ',
+                'i' => 'ᵢ',
+                'j' => 'ⱼ',
+                'k' => 'ₖ',
+                'l' => 'ₗ',
+                'm' => 'ₘ',
+                'n' => 'ₙ',
+                'o' => 'ₒ',
+                'p' => 'ₚ',
+                'r' => 'ᵣ',
+                's' => 'ₛ',
+                't' => 'ₜ',
+                'u' => 'ᵤ',
+                'v' => 'ᵥ',
+                'x' => 'ₓ',
+                ' ' => ' ',
+                _ => return None,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{nodes::word::WordType, parser::parse_markdown};
+
+    fn words(md: &str) -> Vec<(String, WordType)> {
+        parse_markdown(None, md, 80)
+            .components()
+            .into_iter()
+            .flat_map(|c| c.content().iter().flatten().chain(c.meta_info()))
+            .map(|w| (w.content().to_owned(), w.kind()))
+            .collect()
+    }
+
+    #[test]
+    fn formatting_tags_style_their_words() {
+        let words = words("Press <kbd>Ctrl</kbd> and H<sub>2</sub>O, <b>bold</b> x<sup>n</sup>\n");
+        let key: String = words
+            .iter()
+            .filter(|(_, kind)| *kind == WordType::Kbd)
+            .map(|(w, _)| w.as_str())
+            .collect();
+        assert_eq!(key, " Ctrl ");
+        assert!(words.contains(&("₂".to_owned(), WordType::Subscript)));
+        assert!(words.contains(&("ⁿ".to_owned(), WordType::Superscript)));
+        assert!(words.contains(&("bold".to_owned(), WordType::Bold)));
+        assert!(!words.iter().any(|(w, _)| w.contains('<')));
+    }
+
+    #[test]
+    fn line_break_splits_the_paragraph() {
+        let root = parse_markdown(None, "first<br>second\n", 80);
+        let paragraph = root.components().into_iter().next().unwrap();
+        assert_eq!(paragraph.height(), 2);
+    }
+
+    #[test]
+    fn anchor_tag_is_a_link() {
+        let words = words("See <a href=\"https://example.com\">the site</a> now\n");
+        assert!(words.contains(&("the site".to_owned(), WordType::Link)));
+        assert!(words.contains(&("https://example.com".to_owned(), WordType::LinkData)));
+    }
+
+    #[test]
+    fn image_only_paragraph_is_an_image() {
+        // The image can't be fetched in tests, which leaves its description
+        let words = words("<p align=\"center\">\n  <img src=\"logo.png\" alt=\"Logo\">\n</p>\n");
+        assert!(words.contains(&("Image".to_owned(), WordType::Normal)));
+        assert!(words.contains(&("[Logo]".to_owned(), WordType::Normal)));
+    }
+
+    #[test]
+    fn unknown_tags_keep_their_text() {
+        let words = words("a <span class=\"x\">kept</span> word\n");
+        assert!(words.contains(&("kept".to_owned(), WordType::Normal)));
+        assert!(!words.iter().any(|(w, _)| w.contains("span")));
+    }
+
+    #[test]
+    fn tag_only_lines_are_dropped() {
+        let words = words("<div align=\"center\">\n\nText\n\n</div>\n");
+        assert!(words.contains(&("Text".to_owned(), WordType::Normal)));
+        assert!(!words.iter().any(|(w, _)| w.contains("div")));
+    }
+}
@@ -0,0 +1,209 @@
+//! Reference links. The grammar parses `[text][label]` and `[label][]` like
+//! inline links with the label in place of the url, and `[label]: url "title"`
+//! definitions as blocks of their own. A definition may come after the links
+//! using it, so they are resolved once the whole document is parsed.
+
+use std::collections::HashMap;
+
+use itertools::Itertools;
+
+use super::{MdParseEnum, ParseNode};
+
+struct Definition {
+    url: String,
+    title: Option<String>,
+}
+
+/// Replace the labels of reference links with the url they are defined as and
+/// drop the definitions. References without a definition are kept as the text
+/// they were written as. Returns the link titles by url.
+pub(super) fn resolve(nodes: Vec<ParseNode>) -> (Vec<ParseNode>, HashMap<String, String>) {
+    let mut definitions = HashMap::new();
+    collect_definitions(&nodes, &mut definitions);
+
+    let mut titles = HashMap::new();
+    let nodes = resolve_children(nodes, &definitions, &mut titles);
+    (nodes, titles)
+}
+
+/// Labels match case-insensitively and with whitespace collapsed
+pub(super) fn normalize(label: &str) -> String {
+    label.split_whitespace().join(" ").to_lowercase()
+}
+
+fn collect_definitions(nodes: &[ParseNode], definitions: &mut HashMap<String, Definition>) {
+    for node in nodes {
+        if node.kind() != MdParseEnum::LinkReferenceDefinition {
+            collect_definitions(node.children(), definitions);
+            continue;
+        }
+
+        let mut label = None;
+        let mut definition = Definition {
+            url: String::new(),
+            title: None,
+        };
+        for child in node.children() {
+            match child.kind() {
+                MdParseEnum::LinkReference => label = Some(normalize(child.content())),
+                MdParseEnum::LinkData => child.content().clone_into(&mut definition.url),
+                MdParseEnum::LinkTitle => definition.title = Some(child.content().to_owned()),
+                _ => {}
+            }
+        }
+
+        // The first definition of a label wins
+        if let Some(label) = label {
+            definitions.entry(label).or_insert(definition);
+        }
+    }
+}
+
+fn resolve_children(
+    nodes: Vec<ParseNode>,
+    definitions: &HashMap<String, Definition>,
+    titles: &mut HashMap<String, String>,
+) -> Vec<ParseNode> {
+    let mut resolved: Vec<ParseNode> = Vec::with_capacity(nodes.len());
+    for mut node in nodes {
+        match node.kind() {
+            MdParseEnum::LinkReferenceDefinition => {}
+            // A title belongs to the url right before it
+            MdParseEnum::LinkTitle => {
+                if let Some(url) = resolved
+                    .iter()
+                    .rev()
+                    .find(|n| n.kind() == MdParseEnum::LinkData)
+                    && !node.content().is_empty()
+                {
+                    titles
+                        .entry(url.content().to_owned())
+                        .or_insert(node.content);
+                }
+            }
+            MdParseEnum::Link
+                if node
+                    .children()
+                    .iter()
+                    .any(|c| c.kind() == MdParseEnum::LinkReference) =>
+            {
+                resolved.extend(resolve_reference(node, definitions, titles));
+            }
+            _ => {
+                let children = std::mem::take(&mut node.children);
+                node.children = resolve_children(children, definitions, titles);
+                resolved.push(node);
+            }
+        }
+    }
+
+    // Dropped definitions leave the blank lines around them behind
+    resolved
+        .into_iter()
+        .dedup_by(|a, b| {
+            a.kind() == MdParseEnum::BlockSeparator && b.kind() == MdParseEnum::BlockSeparator
+        })
+        .collect()
+}
+
+fn resolve_reference(
+    mut node: ParseNode,
+    definitions: &HashMap<String, Definition>,
+    titles: &mut HashMap<String, String>,
+) -> Vec<ParseNode> {
+    let index = node
+        .children()
+        .iter()
+        .position(|c| c.kind() == MdParseEnum::LinkReference)
+        .expect("reference links have a label");
+
+    // `[label][]` uses the link text as the label
+    let label = match node.children()[index].content() {
+        "" => node
+            .children()
+            .iter()
+            .filter(|c| c.kind() == MdParseEnum::Link)
+            .map(ParseNode::content)
+            .collect(),
+        label => label.to_owned(),
+    };
+
+    let Some(definition) = definitions.get(&normalize(&label)) else {
+        return plain_words(node.content());
+    };
+
+    if let Some(title) = &definition.title
+        && !title.is_empty()
+    {
+        titles
+            .entry(definition.url.clone())
+            .or_insert_with(|| title.clone());
+    }
+    node.children[index] = ParseNode::new(MdParseEnum::LinkData, definition.url.clone());
+    vec![node]
+}
+
+/// Split text into words the way the grammar does, with the spaces before a
+/// word kept in it
+fn plain_words(text: &str) -> Vec<ParseNode> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    for c in text.chars() {
+        if c == ' ' && !word.trim().is_empty() {
+            words.push(ParseNode::new(MdParseEnum::Word, std::mem::take(&mut word)));
+        }
+        word.push(c);
+    }
+    if !word.trim().is_empty() {
+        words.push(ParseNode::new(MdParseEnum::Word, word));
+    }
+    words
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{nodes::word::WordType, parser::parse_markdown};
+
+    fn words(md: &str) -> Vec<(String, WordType)> {
+        parse_markdown(None, md, 80)
+            .components()
+            .into_iter()
+            .flat_map(|c| c.content().iter().flatten().chain(c.meta_info()))
+            .map(|w| (w.content().to_owned(), w.kind()))
+            .collect()
+    }
+
+    #[test]
+    fn reference_links_resolve_to_their_definition() {
+        let md = "See [the docs][Docs] and [crates][].\n\n[docs]: https://docs.rs\n[crates]: <https://crates.io>\n";
+        let words = words(md);
+        assert!(words.contains(&("https://docs.rs".to_owned(), WordType::LinkData)));
+        assert!(words.contains(&("https://crates.io".to_owned(), WordType::LinkData)));
+        assert!(words.contains(&("the docs".to_owned(), WordType::Link)));
+        assert!(!words.iter().any(|(w, _)| w.contains("[docs]")));
+    }
+
+    #[test]
+    fn shortcut_reference_in_heading() {
+        let words = words("## [MSRV] policy\n\n[msrv]: https://msrv.example\n");
+        assert!(words.contains(&("MSRV".to_owned(), WordType::Link)));
+        assert!(words.contains(&("https://msrv.example".to_owned(), WordType::LinkData)));
+    }
+
+    #[test]
+    fn undefined_reference_is_kept_as_text() {
+        let words = words("A [missing][nope] and [unknown] link\n");
+        assert!(words.contains(&("[missing][nope]".to_owned(), WordType::Normal)));
+        assert!(words.contains(&("[unknown]".to_owned(), WordType::Normal)));
+        assert!(!words.iter().any(|(_, kind)| *kind == WordType::LinkData));
+    }
+
+    #[test]
+    fn titles_are_kept_by_url() {
+        let md =
+            "[a][x] and [b](https://b.example \"Inline\")\n\n[x]: https://x.example 'Defined'\n";
+        let root = parse_markdown(None, md, 80);
+        assert_eq!(root.link_title("https://x.example"), Some("Defined"));
+        assert_eq!(root.link_title("https://b.example"), Some("Inline"));
+    }
+}
@@ -75,11 +75,11 @@ pub fn find_md_files_channel(tx: Sender<Option<MdFile>>) {
                     continue;
                 }
 
-                tx.send(Some(MdFile::new(
-                    path_str.to_string(),
-                    path_name.to_string(),
-                )))
-                .unwrap();
+                let mut file = MdFile::new(path_str.to_string(), path_name.to_string());
+                file.tags = std::fs::read_to_string(&path)
+                    .map(|text| find_tags(&text))
+                    .unwrap_or_default();
+                tx.send(Some(file)).unwrap();
             } else if let (Some(file_name), Some(path)) = (path.file_name(), path.to_str())
                 && GENERAL_CONFIG.gitignore
                 && file_name == ".gitignore"
@@ -314,21 +314,140 @@ fn char_windows(src: &str, win_size: usize) -> impl Iterator<Item = &'_ str> {
     })
 }
 
+/// The anchor GitHub gives a heading: lowercase, with punctuation and symbols
+/// removed and every space turned into a `-`.
 #[must_use]
-pub fn compare_heading(link_header: &str, header: &[Vec<Word>]) -> bool {
-    let header: String = header
-        .iter()
-        .flatten()
-        .map(|word| word.content().to_lowercase())
-        .join("-")
-        .trim_start_matches('-')
+pub fn heading_slug(text: &str) -> String {
+    text.trim()
+        .to_lowercase()
         .chars()
-        .filter(|c| c.is_alphanumeric() || *c == '-')
-        .dedup_by(|a, b| *a == '-' && *b == '-')
-        .skip_while(|c| *c == '-')
-        .collect();
+        .filter_map(|c| match c {
+            ' ' => Some('-'),
+            '-' | '_' => Some(c),
+            c if c.is_alphanumeric() => Some(c),
+            _ => None,
+        })
+        .collect()
+}
 
-    link_header == header
+/// Decode the `%XX` escapes in the anchor of a link, so `#caf%C3%A9` finds the
+/// heading `Café`
+#[must_use]
+pub fn decode_anchor(anchor: &str) -> String {
+    let bytes = anchor.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match hex {
+            Some(byte) if bytes[i] == b'%' => {
+                decoded.push(byte);
+                i += 3;
+            }
+            _ => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8(decoded).unwrap_or_else(|_| anchor.to_owned())
+}
+
+/// The length of the `#tag` at the start of `text`, `#` included. Tags are
+/// letters, digits, `_`, `-` and `/` for nested tags, with at least one
+/// character that isn't a digit, so `#1` is no tag.
+#[must_use]
+pub fn tag_len(text: &str) -> Option<usize> {
+    let name = text.strip_prefix('#')?;
+    let len = name
+        .find(|c: char| !(c.is_alphanumeric() || "_-/".contains(c)))
+        .unwrap_or(name.len());
+    let name = &name[..len];
+    if name.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    Some(len + 1)
+}
+
+/// The tags of a note, from `tags:` in its front matter and `#tags` in the
+/// text outside of code. Tags are lowercased and come without the `#`.
+#[must_use]
+pub fn find_tags(text: &str) -> Vec<String> {
+    let mut tags = Vec::new();
+    let mut lines = text.lines().peekable();
+
+    if lines.next_if(|line| line.trim_end() == "---").is_some() {
+        let mut in_tags = false;
+        for line in lines.by_ref() {
+            if matches!(line.trim_end(), "---" | "...") {
+                break;
+            }
+            if let Some(item) = line.trim_start().strip_prefix("- ")
+                && in_tags
+                && line.starts_with(char::is_whitespace)
+            {
+                tags.extend(front_matter_tags(item));
+                continue;
+            }
+            in_tags = false;
+            if let Some((key, value)) = line.split_once(':')
+                && matches!(key.trim(), "tags" | "tag")
+            {
+                in_tags = value.trim().is_empty();
+                tags.extend(front_matter_tags(value));
+            }
+        }
+    }
+
+    let mut fence: Option<&str> = None;
+    for line in lines {
+        let trimmed = line.trim_start();
+        if let Some(marker) = fence {
+            if trimmed.starts_with(marker) {
+                fence = None;
+            }
+            continue;
+        }
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            fence = Some(&trimmed[..3]);
+            continue;
+        }
+
+        let mut in_code = false;
+        let mut before = None;
+        for (i, c) in line.char_indices() {
+            if c == '`' {
+                in_code = !in_code;
+            } else if c == '#'
+                && !in_code
+                && before.is_none_or(char::is_whitespace)
+                && let Some(len) = tag_len(&line[i..])
+            {
+                tags.push(line[i + 1..i + len].to_lowercase());
+            }
+            before = Some(c);
+        }
+    }
+
+    tags.sort_unstable();
+    tags.dedup();
+    tags
+}
+
+/// Tags in a front matter value, like `[a, b]`, `a, b` or `"#a"`
+fn front_matter_tags(value: &str) -> Vec<String> {
+    value
+        .trim()
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .split([',', ' '])
+        .map(|tag| tag.trim().trim_matches(['"', '\'']).trim_start_matches('#'))
+        .filter(|tag| !tag.is_empty())
+        .map(str::to_lowercase)
+        .collect()
 }
 
 #[cfg(test)]
@@ -344,6 +463,33 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn heading_slugs_match_github() {
+        assert_eq!(heading_slug("Getting Started"), "getting-started");
+        assert_eq!(heading_slug("What's new in v0.10?"), "whats-new-in-v010");
+        assert_eq!(
+            heading_slug("snake_case & kebab-case"),
+            "snake_case--kebab-case"
+        );
+        assert_eq!(heading_slug("Café Übersicht"), "café-übersicht");
+        assert_eq!(heading_slug("🚀 Launch"), "-launch");
+    }
+
+    #[test]
+    fn tags_come_from_front_matter_and_text() {
+        let text = "---\ntitle: Notes\ntags: [Rust, \"#cli\"]\naliases:\n  - nope\ntag:\n  - project/mdt\n---\n# Heading\n\nAbout #rust and #tui-apps, not #1 or a#b or `#code`.\n\n```\n#include\n```\n";
+        assert_eq!(find_tags(text), ["cli", "project/mdt", "rust", "tui-apps"]);
+        assert_eq!(tag_len("#a/b-c, d"), Some(6));
+        assert_eq!(tag_len("#2024"), None);
+        assert_eq!(tag_len("# Heading"), None);
+    }
+
+    #[test]
+    fn anchors_are_percent_decoded() {
+        assert_eq!(decode_anchor("caf%C3%A9"), "café");
+        assert_eq!(decode_anchor("100%-sure"), "100%-sure");
+    }
+
     #[test]
     fn test_find() {
         let text = "Hello, world!";
@@ -8,17 +8,28 @@ use crossterm::{
 };
 use general::GENERAL_CONFIG;
 
-use crate::boxes::{errorbox::ErrorBox, help_box::HelpBox, linkbox::LinkBox, searchbox::SearchBox};
+use crate::{
+    boxes::{errorbox::ErrorBox, help_box::HelpBox, linkbox::LinkBox, searchbox::SearchBox},
+    nodes::root::ComponentRoot,
+    pages::tag_browser::TagBrowser,
+    util::hints::LinkHints,
+};
 
+pub mod clipboard;
 pub mod colors;
 pub mod general;
+pub mod hints;
+pub mod hyperlink;
 pub mod keys;
+pub mod piped;
 
 #[derive(Debug, Clone, Copy, PartialEq, Default)]
 pub enum Mode {
     View,
     #[default]
     FileTree,
+    /// The tags of the files in the file tree
+    Tags,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Default)]
@@ -26,10 +37,22 @@ pub enum Boxes {
     Error,
     Search,
     LinkPreview,
+    TableFilter,
+    TableExport,
+    /// Asks before opening an external link, see `App::open_url`
+    ConfirmOpen,
     #[default]
     None,
 }
 
+impl Boxes {
+    /// Boxes the user types into, where `q` doesn't quit
+    #[must_use]
+    pub fn takes_input(self) -> bool {
+        matches!(self, Self::Search | Self::TableFilter | Self::TableExport)
+    }
+}
+
 impl From<JumpHistory> for Mode {
     fn from(jump_history: JumpHistory) -> Self {
         match jump_history.history.last() {
@@ -50,6 +73,23 @@ pub struct App {
     pub select_index: usize,
     pub details_selected: bool,
     pub details_select_index: usize,
+    pub table_selected: bool,
+    pub table_select_index: usize,
+    /// Where to scroll back to after following footnote references
+    pub footnote_returns: Vec<u16>,
+    /// Stick to the bottom of the document when it grows, like `tail -f`
+    pub follow: bool,
+    /// Following stopped by scrolling up, scrolling back to the end starts it
+    /// again
+    pub follow_paused: bool,
+    /// The fold key was pressed, the next key says what to fold
+    pub fold_pending: bool,
+    /// Labels over the links on screen, while picking one by its label
+    pub link_hints: Option<LinkHints>,
+    /// The external link waiting for the user to confirm opening it
+    pub open_url: String,
+    /// Copy external links instead of opening them, set by `--no-open`
+    pub no_open: bool,
     pub mode: Mode,
     pub boxes: Boxes,
     pub history: JumpHistory,
@@ -57,6 +97,7 @@ pub struct App {
     pub message_box: ErrorBox,
     pub help_box: HelpBox,
     pub link_box: LinkBox,
+    pub tag_browser: TagBrowser,
 }
 
 impl App {
@@ -66,10 +107,58 @@ impl App {
         self.select_index = 0;
         self.details_selected = false;
         self.details_select_index = 0;
+        self.table_selected = false;
+        self.table_select_index = 0;
+        self.footnote_returns.clear();
+        self.fold_pending = false;
+        self.link_hints = None;
         self.boxes = Boxes::None;
         self.help_box.close();
     }
 
+    /// Tell the user if parts of a freshly loaded document could not be
+    /// parsed and are shown as plain text.
+    pub fn report_parse_errors(&mut self, markdown: &ComponentRoot) {
+        let Some(first) = markdown.parse_errors().first() else {
+            return;
+        };
+        let more = markdown.parse_errors().len() - 1;
+        let mut message = format!(
+            "Could not parse line {}, column {}. Showing it as plain text.",
+            first.line, first.column
+        );
+        if more > 0 {
+            message.push_str(&format!(" ({more} more)"));
+        }
+        message.push_str(" Run mdt --diagnose for details.");
+        self.message_box.set_message(message);
+        self.boxes = Boxes::Error;
+    }
+
+    /// After the document changed, scroll to its end when following and
+    /// else keep the scroll inside it
+    pub fn keep_in_view(&mut self, markdown_height: u16, height: u16) {
+        let end = follow_scroll(markdown_height, height);
+        self.vertical_scroll = if self.follow {
+            end
+        } else {
+            cmp::min(self.vertical_scroll, end)
+        };
+    }
+
+    /// Scrolling up from the end stops following, scrolling back down to it
+    /// follows again
+    pub fn update_follow(&mut self, markdown_height: u16, height: u16) {
+        let at_end = self.vertical_scroll >= follow_scroll(markdown_height, height);
+        if self.follow && !at_end {
+            self.follow = false;
+            self.follow_paused = true;
+        } else if self.follow_paused && at_end {
+            self.follow = true;
+            self.follow_paused = false;
+        }
+    }
+
     pub fn set_width(&mut self, width: u16) -> bool {
         let temp_width = self.width;
         self.width = cmp::min(width, GENERAL_CONFIG.width);
@@ -82,18 +171,60 @@ impl App {
     }
 }
 
+/// The scroll that shows the end of the document, and where following keeps
+/// it
+#[must_use]
+pub fn follow_scroll(markdown_height: u16, height: u16) -> u16 {
+    markdown_height.saturating_sub(height / 2)
+}
+
+/// The lines of the terminal the document is shown in, above the help bar
+#[must_use]
+pub fn view_height(height: u16) -> u16 {
+    if GENERAL_CONFIG.help_menu {
+        height.saturating_sub(5)
+    } else {
+        height
+    }
+}
+
 pub enum LinkType<'a> {
     Internal(&'a str),
     External(&'a str),
     MarkdownFile(&'a str),
 }
 
+/// The scheme of a URL, like `https` or `mailto`
+#[must_use]
+pub fn url_scheme(url: &str) -> Option<&str> {
+    url.split_once(':')
+        .map(|(scheme, _)| scheme)
+        .filter(|scheme| {
+            scheme.len() > 1
+                && scheme
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+        })
+}
+
+/// Whether links with the scheme of `url` may be opened, see `link_schemes`.
+/// A link without a scheme is a file.
+#[must_use]
+pub fn is_allowed_scheme(url: &str) -> bool {
+    let scheme = url_scheme(url).unwrap_or("file");
+    GENERAL_CONFIG
+        .link_schemes
+        .iter()
+        .any(|allowed| allowed.eq_ignore_ascii_case(scheme))
+}
+
 impl<'a> From<&'a str> for LinkType<'a> {
     fn from(s: &'a str) -> Self {
         if s.starts_with('#') {
             return Self::Internal(s);
         }
-        if s.ends_with("md") || !s.contains('.') {
+        let path = s.split_once('#').map_or(s, |(path, _)| path);
+        if path.ends_with("md") || !path.contains('.') {
             return Self::MarkdownFile(s);
         }
         Self::External(s)
@@ -158,3 +289,39 @@ fn test_jump_history() {
     assert_eq!(jump_history.pop(), Jump::FileTree);
     assert_eq!(jump_history.pop(), Jump::FileTree);
 }
+
+#[cfg(test)]
+#[test]
+fn only_allowed_schemes_are_opened() {
+    assert_eq!(url_scheme("https://example.com"), Some("https"));
+    assert_eq!(url_scheme("C:/file.png"), None);
+    assert_eq!(url_scheme("file.png"), None);
+    assert!(is_allowed_scheme("HTTPS://example.com"));
+    assert!(is_allowed_scheme("mailto:me@example.com"));
+    assert!(!is_allowed_scheme("file:///etc/passwd"));
+    assert!(!is_allowed_scheme("vscode://open?file=x"));
+    assert!(!is_allowed_scheme("script.sh"));
+}
+
+#[cfg(test)]
+#[test]
+fn follow_stops_when_scrolling_up_and_resumes_at_the_end() {
+    let mut app = App {
+        follow: true,
+        ..App::default()
+    };
+    app.keep_in_view(100, 20);
+    assert_eq!(app.vertical_scroll, 90);
+
+    app.vertical_scroll = 80;
+    app.update_follow(100, 20);
+    assert!(!app.follow);
+    app.keep_in_view(120, 20);
+    assert_eq!(app.vertical_scroll, 80);
+
+    app.vertical_scroll = 110;
+    app.update_follow(120, 20);
+    assert!(app.follow);
+    app.keep_in_view(150, 20);
+    assert_eq!(app.vertical_scroll, 140);
+}
@@ -0,0 +1,26 @@
+//! Copying to the system clipboard with the OSC 52 escape sequence. The
+//! terminal does the copying, so it works over ssh and without a clipboard
+//! tool installed, as long as the terminal supports it.
+
+use std::io::{self, Write};
+
+use base64::{Engine, engine::general_purpose::STANDARD};
+
+/// The escape sequence asking the terminal to put the text on the clipboard
+#[must_use]
+pub fn osc52(text: &str) -> String {
+    format!("\x1b]52;c;{}\x07", STANDARD.encode(text))
+}
+
+/// Put the text on the clipboard. Terminals without OSC 52 ignore it.
+pub fn copy_to_clipboard(text: &str) -> io::Result<()> {
+    let mut stdout = io::stdout();
+    stdout.write_all(osc52(text).as_bytes())?;
+    stdout.flush()
+}
+
+#[cfg(test)]
+#[test]
+fn osc52_is_base64_encoded() {
+    assert_eq!(osc52("a,b\n"), "\x1b]52;c;YSxiCg==\x07");
+}
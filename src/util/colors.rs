@@ -18,6 +18,16 @@ pub struct ColorConfig {
     pub link_color: Color,
     pub link_selected_fg_color: Color,
     pub link_selected_bg_color: Color,
+    pub kbd_fg_color: Color,
+    pub kbd_bg_color: Color,
+    pub mark_fg_color: Color,
+    pub mark_bg_color: Color,
+    pub subscript_color: Color,
+    pub superscript_color: Color,
+    pub abbreviation_color: Color,
+    pub tag_color: Color,
+    pub link_hint_fg_color: Color,
+    pub link_hint_bg_color: Color,
 
     // Block styles
     pub code_block_bg_color: Color,
@@ -25,7 +35,11 @@ pub struct ColorConfig {
     pub heading_bg_color: Color,
     pub table_header_fg_color: Color,
     pub table_header_bg_color: Color,
+    pub table_cursor_bg_color: Color,
+    pub definition_term_color: Color,
     pub quote_bg_color: Color,
+    pub embed_border_color: Color,
+    pub fold_marker_color: Color,
 
     // File tree
     pub file_tree_selected_fg_color: Color,
@@ -39,6 +53,16 @@ pub struct ColorConfig {
     pub quote_tip: Color,
     pub quote_note: Color,
     pub quote_caution: Color,
+    pub quote_abstract: Color,
+    pub quote_info: Color,
+    pub quote_todo: Color,
+    pub quote_success: Color,
+    pub quote_question: Color,
+    pub quote_failure: Color,
+    pub quote_danger: Color,
+    pub quote_bug: Color,
+    pub quote_example: Color,
+    pub quote_quote: Color,
     pub quote_default: Color,
 }
 
@@ -71,6 +95,18 @@ pub fn read_color_config_from_file() -> ColorConfig {
                 .unwrap_or_default(),
         )
         .unwrap_or(Color::Reset),
+        embed_border_color: Color::from_str(
+            &settings
+                .get::<String>("embed_border_color")
+                .unwrap_or_default(),
+        )
+        .unwrap_or(Color::DarkGray),
+        fold_marker_color: Color::from_str(
+            &settings
+                .get::<String>("fold_marker_color")
+                .unwrap_or_default(),
+        )
+        .unwrap_or(Color::DarkGray),
         quote_bg_color: Color::from_str(
             &settings.get::<String>("quote_bg_color").unwrap_or_default(),
         )
@@ -103,6 +139,56 @@ pub fn read_color_config_from_file() -> ColorConfig {
                 .unwrap_or_default(),
         )
         .unwrap_or(Color::DarkGray),
+        kbd_fg_color: Color::from_str(&settings.get::<String>("kbd_fg_color").unwrap_or_default())
+            .unwrap_or(Color::White),
+        kbd_bg_color: Color::from_str(&settings.get::<String>("kbd_bg_color").unwrap_or_default())
+            .unwrap_or(Color::Rgb(68, 68, 68)),
+        mark_fg_color: Color::from_str(
+            &settings.get::<String>("mark_fg_color").unwrap_or_default(),
+        )
+        .unwrap_or(Color::Black),
+        mark_bg_color: Color::from_str(
+            &settings.get::<String>("mark_bg_color").unwrap_or_default(),
+        )
+        .unwrap_or(Color::Yellow),
+        subscript_color: Color::from_str(
+            &settings
+                .get::<String>("subscript_color")
+                .unwrap_or_default(),
+        )
+        .unwrap_or(Color::Reset),
+        superscript_color: Color::from_str(
+            &settings
+                .get::<String>("superscript_color")
+                .unwrap_or_default(),
+        )
+        .unwrap_or(Color::Reset),
+        abbreviation_color: Color::from_str(
+            &settings
+                .get::<String>("abbreviation_color")
+                .unwrap_or_default(),
+        )
+        .unwrap_or(Color::Reset),
+        tag_color: Color::from_str(&settings.get::<String>("tag_color").unwrap_or_default())
+            .unwrap_or(Color::LightMagenta),
+        link_hint_fg_color: Color::from_str(
+            &settings
+                .get::<String>("link_hint_fg_color")
+                .unwrap_or_default(),
+        )
+        .unwrap_or(Color::Black),
+        link_hint_bg_color: Color::from_str(
+            &settings
+                .get::<String>("link_hint_bg_color")
+                .unwrap_or_default(),
+        )
+        .unwrap_or(Color::Yellow),
+        definition_term_color: Color::from_str(
+            &settings
+                .get::<String>("definition_term_color")
+                .unwrap_or_default(),
+        )
+        .unwrap_or(Color::Reset),
         table_header_fg_color: Color::from_str(
             &settings
                 .get::<String>("table_header_fg_color")
@@ -115,6 +201,12 @@ pub fn read_color_config_from_file() -> ColorConfig {
                 .unwrap_or_default(),
         )
         .unwrap_or(Color::Reset),
+        table_cursor_bg_color: Color::from_str(
+            &settings
+                .get::<String>("table_cursor_bg_color")
+                .unwrap_or_default(),
+        )
+        .unwrap_or(Color::Rgb(48, 48, 48)),
         file_tree_selected_fg_color: Color::from_str(
             &settings
                 .get::<String>("file_tree_selected_fg_color")
@@ -167,6 +259,46 @@ pub fn read_color_config_from_file() -> ColorConfig {
         )
         .unwrap_or(Color::LightMagenta),
 
+        quote_abstract: Color::from_str(
+            &settings.get::<String>("quote_abstract").unwrap_or_default(),
+        )
+        .unwrap_or(Color::Cyan),
+
+        quote_info: Color::from_str(&settings.get::<String>("quote_info").unwrap_or_default())
+            .unwrap_or(Color::LightBlue),
+
+        quote_todo: Color::from_str(&settings.get::<String>("quote_todo").unwrap_or_default())
+            .unwrap_or(Color::LightBlue),
+
+        quote_success: Color::from_str(
+            &settings.get::<String>("quote_success").unwrap_or_default(),
+        )
+        .unwrap_or(Color::Green),
+
+        quote_question: Color::from_str(
+            &settings.get::<String>("quote_question").unwrap_or_default(),
+        )
+        .unwrap_or(Color::Yellow),
+
+        quote_failure: Color::from_str(
+            &settings.get::<String>("quote_failure").unwrap_or_default(),
+        )
+        .unwrap_or(Color::Red),
+
+        quote_danger: Color::from_str(&settings.get::<String>("quote_danger").unwrap_or_default())
+            .unwrap_or(Color::Red),
+
+        quote_bug: Color::from_str(&settings.get::<String>("quote_bug").unwrap_or_default())
+            .unwrap_or(Color::Red),
+
+        quote_example: Color::from_str(
+            &settings.get::<String>("quote_example").unwrap_or_default(),
+        )
+        .unwrap_or(Color::Magenta),
+
+        quote_quote: Color::from_str(&settings.get::<String>("quote_quote").unwrap_or_default())
+            .unwrap_or(Color::Gray),
+
         quote_default: Color::from_str(
             &settings.get::<String>("quote_default").unwrap_or_default(),
         )
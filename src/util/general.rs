@@ -1,14 +1,27 @@
-use std::sync::LazyLock;
+use std::{collections::HashMap, sync::LazyLock};
 
 use config::{Config, Environment, File};
 use serde::Deserialize;
 
+use crate::util::hyperlink::terminal_supports_hyperlinks;
+
 #[derive(Debug)]
 pub struct GeneralConfig {
     pub width: u16,
     pub gitignore: bool,
     pub centering: Centering,
     pub help_menu: bool,
+    /// The name of the parser to use, checked when mdt starts
+    pub parser: Option<String>,
+    /// Icons of callouts by kind, in place of the default ones
+    pub callout_icons: HashMap<String, String>,
+    /// Make links terminal hyperlinks, by default when the terminal is known
+    /// to support them
+    pub hyperlinks: bool,
+    /// The schemes of the external links that may be opened
+    pub link_schemes: Vec<String>,
+    /// Copy external links instead of opening them
+    pub copy_links: bool,
 }
 
 #[derive(Debug, Deserialize)]
@@ -36,5 +49,16 @@ pub static GENERAL_CONFIG: LazyLock<GeneralConfig> = LazyLock::new(|| {
             .get::<Centering>("alignment")
             .unwrap_or(Centering::Left),
         help_menu: settings.get::<bool>("help_menu").unwrap_or(true),
+        parser: settings.get_string("parser").ok(),
+        callout_icons: settings
+            .get::<HashMap<String, String>>("callout_icons")
+            .unwrap_or_default(),
+        hyperlinks: settings
+            .get::<bool>("hyperlinks")
+            .unwrap_or_else(|_| terminal_supports_hyperlinks()),
+        link_schemes: settings
+            .get::<Vec<String>>("link_schemes")
+            .unwrap_or_else(|_| ["http", "https", "mailto"].map(String::from).to_vec()),
+        copy_links: settings.get::<bool>("copy_links").unwrap_or(false),
     }
 });
@@ -0,0 +1,158 @@
+//! Link hints: short labels over every link on screen, typing one picks
+//! that link without stepping through the ones before it.
+
+use crate::nodes::root::ComponentRoot;
+
+/// The keys hint labels are made of, home row first
+const HINT_CHARS: &str = "asdfghjkl";
+
+/// A label and the link it stands for
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hint {
+    pub label: String,
+    /// The index `ComponentRoot::select` takes
+    pub index: usize,
+    /// The line of the document the link starts on
+    pub y: u16,
+    /// The first word of the link, where the label goes
+    pub text: String,
+}
+
+/// What typing a key in hint mode did
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HintMatch {
+    /// The input is the start of some labels, keep typing
+    Pending,
+    /// The input is a whole label, the link with this index
+    Found(usize),
+    /// No label starts like this, the key is ignored
+    None,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct LinkHints {
+    hints: Vec<Hint>,
+    input: String,
+}
+
+impl LinkHints {
+    /// Label the links shown in the `height` lines below `scroll`. `None`
+    /// when there are none.
+    #[must_use]
+    pub fn new(markdown: &ComponentRoot, scroll: u16, height: u16) -> Option<Self> {
+        let links = markdown.visible_links(scroll, height);
+        if links.is_empty() {
+            return None;
+        }
+        let hints = labels(links.len())
+            .into_iter()
+            .zip(links)
+            .map(|(label, (index, y, text))| Hint {
+                label,
+                index,
+                y,
+                text: text.to_owned(),
+            })
+            .collect();
+        Some(Self {
+            hints,
+            input: String::new(),
+        })
+    }
+
+    /// What has been typed so far
+    #[must_use]
+    pub fn input(&self) -> &str {
+        &self.input
+    }
+
+    /// The hints whose label starts with the input
+    pub fn hints(&self) -> impl Iterator<Item = &Hint> {
+        self.hints
+            .iter()
+            .filter(|hint| hint.label.starts_with(&self.input))
+    }
+
+    /// Type a key, case doesn't matter
+    pub fn push(&mut self, c: char) -> HintMatch {
+        let mut input = self.input.clone();
+        input.push(c.to_ascii_lowercase());
+        if let Some(hint) = self.hints.iter().find(|hint| hint.label == input) {
+            return HintMatch::Found(hint.index);
+        }
+        if self.hints.iter().any(|hint| hint.label.starts_with(&input)) {
+            self.input = input;
+            return HintMatch::Pending;
+        }
+        HintMatch::None
+    }
+
+    pub fn pop(&mut self) {
+        self.input.pop();
+    }
+}
+
+/// `count` labels, as short as possible while none is the start of another
+#[must_use]
+pub fn labels(count: usize) -> Vec<String> {
+    let mut labels = vec![String::new()];
+    let mut offset = 0;
+    // Replace the first label with its one key longer versions until there
+    // are enough
+    while labels.len() - offset < count || labels.len() == 1 {
+        let prefix = labels[offset].clone();
+        offset += 1;
+        labels.extend(HINT_CHARS.chars().map(|c| format!("{prefix}{c}")));
+    }
+    labels.drain(..offset);
+    labels.truncate(count);
+    labels
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_markdown;
+
+    #[test]
+    fn labels_are_short_and_no_prefix_of_another() {
+        assert_eq!(labels(3), ["a", "s", "d"]);
+
+        let many = labels(12);
+        assert_eq!(many.len(), 12);
+        assert_eq!(&many[..3], ["s", "d", "f"]);
+        assert_eq!(many.last().map(String::as_str), Some("af"));
+        for a in &many {
+            assert!(many.iter().all(|b| a == b || !b.starts_with(a.as_str())));
+        }
+    }
+
+    #[test]
+    fn typing_a_label_finds_its_link() {
+        let md = (1..=12)
+            .map(|i| format!("Link [number {i}](#n{i})\n"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let mut markdown = parse_markdown(None, &md, 80);
+        markdown.set_scroll(0);
+
+        let mut hints = LinkHints::new(&markdown, 0, 100).unwrap();
+        assert_eq!(hints.hints().count(), 12);
+        assert_eq!(hints.push('x'), HintMatch::None);
+        assert_eq!(hints.push('A'), HintMatch::Pending);
+        assert_eq!(hints.hints().count(), 4);
+        assert_eq!(hints.push('s'), HintMatch::Found(9));
+        hints.pop();
+        assert_eq!(hints.input(), "");
+
+        let last = hints.hints().last().unwrap().clone();
+        assert_eq!(last.text, "number 12");
+        markdown.select(last.index).unwrap();
+        assert_eq!(markdown.selected(), "#n12");
+
+        // Only what is on screen gets a label
+        let hints = LinkHints::new(&markdown, 2, 3).unwrap();
+        assert_eq!(hints.hints().count(), 2);
+        assert!(LinkHints::new(&markdown, 100, 3).is_none());
+    }
+}
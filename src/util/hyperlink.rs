@@ -0,0 +1,142 @@
+//! Terminal hyperlinks with the OSC 8 escape sequence. Links shown this way
+//! can be clicked and copied in the terminal itself.
+
+use std::{env, path::Path};
+
+use super::{LinkType, is_allowed_scheme, url_scheme};
+
+/// Text that links to `url`, terminals without OSC 8 show just the text. The
+/// URL has to be one from `link_url`, control characters in it would end the
+/// sequence.
+#[must_use]
+pub fn osc8(url: &str, text: &str) -> String {
+    format!("\x1b]8;;{url}\x1b\\{text}\x1b]8;;\x1b\\")
+}
+
+/// The text of a symbol, without the OSC 8 sequence around it
+#[must_use]
+pub fn strip_osc8(symbol: &str) -> &str {
+    symbol
+        .strip_prefix("\x1b]8;")
+        .and_then(|rest| rest.split_once("\x1b\\"))
+        .and_then(|(_, rest)| rest.strip_suffix("\x1b]8;;\x1b\\"))
+        .unwrap_or(symbol)
+}
+
+/// Whether the terminal is known to support OSC 8, from what it sets in the
+/// environment
+#[must_use]
+pub fn terminal_supports_hyperlinks() -> bool {
+    let var = |name| env::var(name).unwrap_or_default();
+    // tmux and screen only pass them on when told to
+    if !var("TMUX").is_empty() || var("TERM").starts_with("screen") {
+        return false;
+    }
+    matches!(
+        var("TERM_PROGRAM").as_str(),
+        "iTerm.app" | "WezTerm" | "vscode" | "ghostty" | "Hyper"
+    ) || ["xterm-kitty", "foot", "alacritty", "wezterm", "xterm-ghostty"]
+        .iter()
+        .any(|term| var("TERM").starts_with(term))
+        || ["KITTY_WINDOW_ID", "WT_SESSION", "KONSOLE_VERSION"]
+            .iter()
+            .any(|name| !var(name).is_empty())
+        // GNOME Terminal and other VTE terminals since 0.50
+        || var("VTE_VERSION").parse::<u32>().is_ok_and(|v| v >= 5000)
+}
+
+/// The URL a link target opens, a web address or a file that exists as a
+/// `file://` URL, with everything but printable ASCII percent-encoded. `None`
+/// for headings in the same document, for files that don't exist and for
+/// schemes not in `link_schemes`, where other files than markdown count as
+/// `file`.
+#[must_use]
+pub fn link_url(target: &str) -> Option<String> {
+    let (path, anchor) = match LinkType::from(target) {
+        LinkType::Internal(_) => return None,
+        LinkType::External(url) if url_scheme(url).is_some() => {
+            return is_allowed_scheme(url).then(|| percent_encode(url, ""));
+        }
+        // Files are opened like `Enter` does, from the current directory
+        LinkType::External(path) if is_allowed_scheme(path) => (path.trim_start_matches('/'), None),
+        LinkType::External(_) => return None,
+        LinkType::MarkdownFile(link) => {
+            let link = link.trim_start_matches('/');
+            let (path, anchor) = link
+                .split_once('#')
+                .map_or((link, None), |(path, anchor)| (path, Some(anchor)));
+            if path.ends_with(".md") {
+                (path, anchor)
+            } else {
+                return file_url(Path::new(&format!("{path}.md")), anchor);
+            }
+        }
+    };
+    file_url(Path::new(path), anchor)
+}
+
+fn file_url(path: &Path, anchor: Option<&str>) -> Option<String> {
+    let path = path.canonicalize().ok()?;
+    let mut url = format!("file://{}", percent_encode(&path.to_string_lossy(), "#%?"));
+    if let Some(anchor) = anchor {
+        url.push('#');
+        url.push_str(&percent_encode(anchor, ""));
+    }
+    Some(url)
+}
+
+/// `text` with the bytes of spaces, control characters, anything not ASCII
+/// and of `reserved` as `%XX`
+fn percent_encode(text: &str, reserved: &str) -> String {
+    let mut encoded = String::with_capacity(text.len());
+    for &byte in text.as_bytes() {
+        if byte.is_ascii_graphic() && !reserved.as_bytes().contains(&byte) {
+            encoded.push(char::from(byte));
+        } else {
+            encoded.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn links_become_urls() {
+        assert_eq!(
+            link_url("https://example.com/a?b=c").as_deref(),
+            Some("https://example.com/a?b=c")
+        );
+        assert_eq!(
+            link_url("mailto:me@example.com").as_deref(),
+            Some("mailto:me@example.com")
+        );
+        assert_eq!(link_url("javascript:alert(1)"), None);
+        assert_eq!(link_url("#heading"), None);
+        assert_eq!(link_url("missing-file.md"), None);
+
+        let readme = Path::new("README.md").canonicalize().unwrap();
+        let readme = readme.to_string_lossy();
+        assert_eq!(
+            link_url("README#tags"),
+            Some(format!("file://{readme}#tags"))
+        );
+
+        // Nothing in the target can end the escape sequence early
+        assert_eq!(
+            link_url("https://a.b/\x1b]0;pwned\x07z").as_deref(),
+            Some("https://a.b/%1B]0;pwned%07z")
+        );
+        assert_eq!(
+            link_url("https://a.b/é x\x1b\\").as_deref(),
+            Some("https://a.b/%C3%A9%20x%1B\\")
+        );
+
+        let text = osc8("https://example.com", "a");
+        assert_eq!(text, "\x1b]8;;https://example.com\x1b\\a\x1b]8;;\x1b\\");
+        assert_eq!(strip_osc8(&text), "a");
+        assert_eq!(strip_osc8("b"), "b");
+    }
+}
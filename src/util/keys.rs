@@ -14,6 +14,7 @@ pub enum Action {
     SelectLink,
     SelectLinkAlt,
     SelectDetails,
+    SelectTable,
     SearchNext,
     SearchPrevious,
     Edit,
@@ -25,7 +26,14 @@ pub enum Action {
     Help,
     Back,
     ToFileTree,
+    Tags,
     Sort,
+    Follow,
+    Fold,
+    LinkHints,
+    Yank,
+    YankAlt,
+    Export,
     None,
 }
 
@@ -43,13 +51,21 @@ pub struct KeyConfig {
     pub select_link: char,
     pub select_link_alt: char,
     pub select_details: char,
+    pub select_table: char,
     pub edit: char,
     pub hover: char,
     pub top: char,
     pub bottom: char,
     pub back: char,
     pub file_tree: char,
+    pub tags: char,
     pub sort: char,
+    pub follow: char,
+    pub fold: char,
+    pub link_hints: char,
+    pub yank: char,
+    pub yank_alt: char,
+    pub export: char,
 }
 
 #[must_use]
@@ -96,6 +112,10 @@ pub fn key_to_action(key: KeyCode) -> Action {
                 return Action::SelectDetails;
             }
 
+            if c == KEY_CONFIG.select_table {
+                return Action::SelectTable;
+            }
+
             if c == KEY_CONFIG.search_next {
                 return Action::SearchNext;
             }
@@ -128,10 +148,38 @@ pub fn key_to_action(key: KeyCode) -> Action {
                 return Action::ToFileTree;
             }
 
+            if c == KEY_CONFIG.tags {
+                return Action::Tags;
+            }
+
             if c == KEY_CONFIG.sort {
                 return Action::Sort;
             }
 
+            if c == KEY_CONFIG.follow {
+                return Action::Follow;
+            }
+
+            if c == KEY_CONFIG.fold {
+                return Action::Fold;
+            }
+
+            if c == KEY_CONFIG.link_hints {
+                return Action::LinkHints;
+            }
+
+            if c == KEY_CONFIG.yank {
+                return Action::Yank;
+            }
+
+            if c == KEY_CONFIG.yank_alt {
+                return Action::YankAlt;
+            }
+
+            if c == KEY_CONFIG.export {
+                return Action::Export;
+            }
+
             if c == '?' {
                 return Action::Help;
             }
@@ -170,6 +218,7 @@ pub static KEY_CONFIG: LazyLock<KeyConfig> = LazyLock::new(|| {
         select_link: settings.get::<char>("select_link").unwrap_or('s'),
         select_link_alt: settings.get::<char>("select_link_alt").unwrap_or('S'),
         select_details: settings.get::<char>("select_details").unwrap_or('D'),
+        select_table: settings.get::<char>("select_table").unwrap_or('T'),
         search_next: settings.get::<char>("search_next").unwrap_or('n'),
         search_previous: settings.get::<char>("search_previous").unwrap_or('N'),
         edit: settings.get::<char>("edit").unwrap_or('e'),
@@ -178,6 +227,13 @@ pub static KEY_CONFIG: LazyLock<KeyConfig> = LazyLock::new(|| {
         bottom: settings.get::<char>("bottom").unwrap_or('G'),
         back: settings.get::<char>("back").unwrap_or('b'),
         file_tree: settings.get::<char>("file_tree").unwrap_or('t'),
+        tags: settings.get::<char>("tags").unwrap_or('#'),
         sort: settings.get::<char>("sort").unwrap_or('o'),
+        follow: settings.get::<char>("follow").unwrap_or('F'),
+        fold: settings.get::<char>("fold").unwrap_or('z'),
+        link_hints: settings.get::<char>("link_hints").unwrap_or('L'),
+        yank: settings.get::<char>("yank").unwrap_or('y'),
+        yank_alt: settings.get::<char>("yank_alt").unwrap_or('Y'),
+        export: settings.get::<char>("export").unwrap_or('w'),
     }
 });
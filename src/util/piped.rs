@@ -0,0 +1,111 @@
+//! Markdown piped to stdin, shown while the program writing it is still
+//! running. Chunks arrive on a channel and are collected into the document,
+//! which is parsed again at most as often as keeps the viewer responsive.
+
+use std::{
+    sync::mpsc::{Receiver, TryRecvError},
+    time::{Duration, Instant},
+};
+
+/// How many times as long as the last parse took to wait before the next
+const PARSE_SPACING: u32 = 4;
+
+pub struct PipedInput {
+    chunks: Receiver<String>,
+    text: String,
+    /// Text arrived that hasn't been parsed yet
+    unparsed: bool,
+    /// The writing end is closed, nothing more will arrive
+    ended: bool,
+    /// Parsing before this would take more of the time than
+    /// `PARSE_SPACING` allows
+    next_parse: Option<Instant>,
+}
+
+impl PipedInput {
+    #[must_use]
+    pub fn new(chunks: Receiver<String>) -> Self {
+        Self {
+            chunks,
+            text: String::new(),
+            unparsed: false,
+            ended: false,
+            next_parse: None,
+        }
+    }
+
+    /// All the text received so far
+    #[must_use]
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Take the chunks that arrived. Returns the text when it should be
+    /// parsed again, which for a large document growing in small chunks is
+    /// not after every one of them. The last of the text is never held back.
+    pub fn receive(&mut self, now: Instant) -> Option<&str> {
+        loop {
+            match self.chunks.try_recv() {
+                Ok(chunk) => {
+                    self.text.push_str(&chunk);
+                    self.unparsed = true;
+                }
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    self.ended = true;
+                    break;
+                }
+            }
+        }
+        let due = self.ended || self.next_parse.is_none_or(|next| now >= next);
+        (self.unparsed && due).then_some(self.text.as_str())
+    }
+
+    /// The text from `receive` was parsed at `now`, which took `took`
+    pub fn parsed(&mut self, now: Instant, took: Duration) {
+        self.unparsed = false;
+        self.next_parse = Some(now + took * PARSE_SPACING);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc;
+
+    use super::*;
+
+    #[test]
+    fn chunks_are_collected_and_parsed_spaced_out() {
+        let (tx, rx) = mpsc::channel();
+        let mut piped = PipedInput::new(rx);
+        let start = Instant::now();
+        let ms = Duration::from_millis;
+        assert_eq!(piped.receive(start), None);
+
+        tx.send("# Title\n".to_owned()).unwrap();
+        tx.send("\nFirst".to_owned()).unwrap();
+        assert_eq!(piped.receive(start), Some("# Title\n\nFirst"));
+        piped.parsed(start, ms(10));
+        assert_eq!(piped.receive(start), None);
+
+        // Held back until four times the parse time has passed
+        tx.send(" line\n".to_owned()).unwrap();
+        assert_eq!(piped.receive(start + ms(20)), None);
+        assert_eq!(piped.text(), "# Title\n\nFirst line\n");
+        assert_eq!(
+            piped.receive(start + ms(40)),
+            Some("# Title\n\nFirst line\n")
+        );
+        piped.parsed(start + ms(40), ms(10));
+
+        // The end is shown right away
+        tx.send("Last\n".to_owned()).unwrap();
+        drop(tx);
+        assert_eq!(
+            piped.receive(start + ms(41)),
+            Some("# Title\n\nFirst line\nLast\n")
+        );
+        piped.parsed(start + ms(41), ms(10));
+        assert_eq!(piped.receive(start + ms(100)), None);
+    }
+}
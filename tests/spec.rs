@@ -0,0 +1,678 @@
+//! Runs the CommonMark and GFM spec examples through `parse_markdown` and
+//! compares the result with the HTML the spec expects.
+//!
+//! The comparison is structural: the expected HTML and the parsed components
+//! are both reduced to a list of blocks. Headings and paragraphs also compare
+//! their text and inline styling, and code blocks their content. Everything
+//! else only compares the kind of block, since the component tree doesn't
+//! keep more than that.
+//!
+//! Examples the grammar is known to get wrong are listed in
+//! `tests/spec/expected_failures.txt`. The test fails if an example outside
+//! that list fails, or if one in it starts passing. After changing `md.pest`,
+//! look at the report with
+//!
+//! ```sh
+//! cargo test --test spec -- --nocapture
+//! ```
+//!
+//! and update the list with `MDT_BLESS_SPEC=1 cargo test --test spec`.
+
+use std::{
+    collections::BTreeSet,
+    fmt::{self, Display, Write},
+    panic,
+    path::PathBuf,
+};
+
+use md_tui::{
+    nodes::{
+        root::Component,
+        textcomponent::{Container, TextComponent, TextNode},
+        word::{MetaData, WordType},
+    },
+    parser::{ParserBackend, parse_markdown_with},
+};
+
+const SUITES: [&str; 2] = ["commonmark", "gfm"];
+const EXPECTED_FAILURES: &str = "expected_failures.txt";
+
+struct Example {
+    suite: &'static str,
+    section: String,
+    number: usize,
+    markdown: String,
+    html: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Block {
+    Heading(u8, String),
+    Paragraph(String),
+    Code(String),
+    List,
+    Quote,
+    Table,
+    Rule,
+    Html,
+    Footnote,
+    /// Not part of CommonMark or GFM, so never expected
+    DefinitionList,
+}
+
+impl Display for Block {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Block::Heading(level, text) => write!(f, "h{level} {text:?}"),
+            Block::Paragraph(text) => write!(f, "p {text:?}"),
+            Block::Code(text) => write!(f, "pre {text:?}"),
+            Block::List => write!(f, "list"),
+            Block::Quote => write!(f, "blockquote"),
+            Block::Table => write!(f, "table"),
+            Block::Rule => write!(f, "hr"),
+            Block::Html => write!(f, "html"),
+            Block::Footnote => write!(f, "footnote"),
+            Block::DefinitionList => write!(f, "dl"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Style {
+    Plain,
+    Italic,
+    Bold,
+    BoldItalic,
+    Code,
+    Strikethrough,
+    Link,
+    Image,
+}
+
+impl Style {
+    fn tag(self) -> &'static str {
+        match self {
+            Style::Plain => "",
+            Style::Italic => "i",
+            Style::Bold => "b",
+            Style::BoldItalic => "bi",
+            Style::Code => "code",
+            Style::Strikethrough => "del",
+            Style::Link => "a",
+            Style::Image => "img",
+        }
+    }
+}
+
+/// Text with inline styles, written as `plain <i>italic</i> plain`.
+/// Whitespace is collapsed and never styled, so differences in where the
+/// spaces between words end up don't matter.
+#[derive(Default)]
+struct StyledText {
+    chars: Vec<(Style, char)>,
+}
+
+impl StyledText {
+    fn push(&mut self, style: Style, text: &str) {
+        for c in text.chars() {
+            if c.is_whitespace() {
+                if self.chars.last().is_some_and(|(_, last)| *last != ' ') {
+                    self.chars.push((Style::Plain, ' '));
+                }
+            } else {
+                self.chars.push((style, c));
+            }
+        }
+    }
+
+    fn finish(mut self) -> String {
+        while self.chars.last().is_some_and(|(_, c)| *c == ' ') {
+            self.chars.pop();
+        }
+
+        let mut out = String::new();
+        let mut current = Style::Plain;
+        for (style, c) in self.chars {
+            if c != ' ' && style != current {
+                if current != Style::Plain {
+                    let _ = write!(out, "</{}>", current.tag());
+                }
+                if style != Style::Plain {
+                    let _ = write!(out, "<{}>", style.tag());
+                }
+                current = style;
+            }
+            out.push(c);
+        }
+        if current != Style::Plain {
+            let _ = write!(out, "</{}>", current.tag());
+        }
+        out
+    }
+}
+
+fn spec_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("spec")
+}
+
+/// Read the examples of a file in the format of the spec's `spec.txt`. They
+/// are numbered from 1, or from the number of a `<!-- number N -->` line.
+fn load_examples(suite: &'static str) -> Vec<Example> {
+    const FENCE: &str = "````````````````````````````````";
+
+    let path = spec_dir().join(format!("{suite}.txt"));
+    let text = std::fs::read_to_string(&path)
+        .unwrap_or_else(|e| panic!("could not read {}: {e}", path.display()));
+
+    let mut examples = Vec::new();
+    let mut section = String::new();
+    let mut number = 1;
+    let mut lines = text.lines();
+    while let Some(line) = lines.next() {
+        if let Some(heading) = line.strip_prefix("## ") {
+            section = heading.trim().to_string();
+        } else if let Some(start) = line
+            .strip_prefix("<!-- number ")
+            .and_then(|rest| rest.strip_suffix(" -->"))
+        {
+            number = start
+                .parse()
+                .unwrap_or_else(|e| panic!("bad example number {start:?}: {e}"));
+        } else if line == format!("{FENCE} example") {
+            let mut markdown = String::new();
+            let mut html = String::new();
+            let mut in_html = false;
+            for line in lines.by_ref() {
+                if line == FENCE {
+                    break;
+                } else if line == "." && !in_html {
+                    in_html = true;
+                } else if in_html {
+                    html.push_str(line);
+                    html.push('\n');
+                } else {
+                    markdown.push_str(line);
+                    markdown.push('\n');
+                }
+            }
+            examples.push(Example {
+                suite,
+                section: section.clone(),
+                number,
+                markdown: markdown.replace('→', "\t"),
+                html: html.replace('→', "\t"),
+            });
+            number += 1;
+        }
+    }
+    examples
+}
+
+enum Token<'a> {
+    Open(&'a str, &'a str),
+    Close(&'a str),
+    Text(&'a str),
+    Other,
+}
+
+fn tokenize(html: &str) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    let mut rest = html;
+    while !rest.is_empty() {
+        let Some(start) = rest.find('<') else {
+            tokens.push(Token::Text(rest));
+            break;
+        };
+        if start > 0 {
+            tokens.push(Token::Text(&rest[..start]));
+            rest = &rest[start..];
+        }
+
+        let terminator = if rest.starts_with("<!--") {
+            "-->"
+        } else if rest.starts_with("<?") {
+            "?>"
+        } else if rest.starts_with("<![CDATA[") {
+            "]]>"
+        } else {
+            ">"
+        };
+        let end = rest[1..]
+            .find(terminator)
+            .map_or(rest.len(), |i| i + 1 + terminator.len());
+        let tag = &rest[..end];
+        rest = &rest[end..];
+
+        let inner = tag.trim_start_matches('<').trim_end_matches('>');
+        if let Some(name) = inner.strip_prefix('/') {
+            tokens.push(Token::Close(name.trim()));
+        } else if inner.starts_with(|c: char| c.is_ascii_alphabetic()) {
+            let name_end = inner
+                .find(|c: char| !c.is_ascii_alphanumeric())
+                .unwrap_or(inner.len());
+            tokens.push(Token::Open(&inner[..name_end], tag));
+        } else {
+            tokens.push(Token::Other);
+        }
+    }
+    tokens
+}
+
+fn decode_entities(text: &str) -> String {
+    let mut out = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let decoded = rest.find(';').and_then(|end| {
+            let entity = &rest[1..end];
+            let c = match entity {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                _ => entity
+                    .strip_prefix("#x")
+                    .or_else(|| entity.strip_prefix("#X"))
+                    .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                    .or_else(|| entity.strip_prefix('#').and_then(|d| d.parse().ok()))
+                    .and_then(char::from_u32),
+            };
+            c.map(|c| (c, end))
+        });
+        if let Some((c, end)) = decoded {
+            out.push(c);
+            rest = &rest[end + 1..];
+        } else {
+            out.push('&');
+            rest = &rest[1..];
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+fn inline_text(tokens: &[Token<'_>]) -> String {
+    let mut text = StyledText::default();
+    let (mut italic, mut bold, mut code, mut del, mut link) = (0, 0, 0, 0, 0);
+    for token in tokens {
+        match token {
+            Token::Open(name, tag) => match *name {
+                "em" => italic += 1,
+                "strong" => bold += 1,
+                "code" => code += 1,
+                "del" => del += 1,
+                "a" => link += 1,
+                "br" => text.push(Style::Plain, " "),
+                "img" => {
+                    let alt = tag
+                        .split_once("alt=\"")
+                        .and_then(|(_, rest)| rest.split_once('"'))
+                        .map_or("", |(alt, _)| alt);
+                    text.push(Style::Image, &decode_entities(alt));
+                }
+                // Raw HTML the spec passes through, a browser doesn't show
+                // the tags and neither does mdt
+                _ => {}
+            },
+            Token::Close(name) => match *name {
+                "em" => italic -= 1,
+                "strong" => bold -= 1,
+                "code" => code -= 1,
+                "del" => del -= 1,
+                "a" => link -= 1,
+                _ => {}
+            },
+            Token::Text(s) => {
+                let style = if link > 0 {
+                    Style::Link
+                } else if code > 0 {
+                    Style::Code
+                } else if bold > 0 && italic > 0 {
+                    Style::BoldItalic
+                } else if bold > 0 {
+                    Style::Bold
+                } else if italic > 0 {
+                    Style::Italic
+                } else if del > 0 {
+                    Style::Strikethrough
+                } else {
+                    Style::Plain
+                };
+                text.push(style, &decode_entities(s));
+            }
+            Token::Other => {}
+        }
+    }
+    text.finish()
+}
+
+fn plain_text(tokens: &[Token<'_>]) -> String {
+    tokens
+        .iter()
+        .filter_map(|token| match token {
+            Token::Text(s) => Some(decode_entities(s)),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Reduce the expected HTML of an example to its top level blocks.
+fn expected_blocks(html: &str) -> Vec<Block> {
+    let tokens = tokenize(html);
+    let mut blocks = Vec::new();
+    let mut i = 0;
+    while i < tokens.len() {
+        match &tokens[i] {
+            Token::Open(name, _)
+                if matches!(
+                    *name,
+                    "p" | "h1"
+                        | "h2"
+                        | "h3"
+                        | "h4"
+                        | "h5"
+                        | "h6"
+                        | "ul"
+                        | "ol"
+                        | "pre"
+                        | "blockquote"
+                        | "table"
+                ) =>
+            {
+                let mut depth = 0;
+                let mut end = i + 1;
+                while end < tokens.len() {
+                    match &tokens[end] {
+                        Token::Open(n, _) if n == name => depth += 1,
+                        Token::Close(n) if n == name => {
+                            if depth == 0 {
+                                break;
+                            }
+                            depth -= 1;
+                        }
+                        _ => {}
+                    }
+                    end += 1;
+                }
+                let inner = &tokens[i + 1..end.min(tokens.len())];
+                blocks.push(match *name {
+                    "p" => Block::Paragraph(inline_text(inner)),
+                    "ul" | "ol" => Block::List,
+                    "pre" => Block::Code(plain_text(inner)),
+                    "blockquote" => Block::Quote,
+                    "table" => Block::Table,
+                    heading => Block::Heading(heading[1..].parse().unwrap(), inline_text(inner)),
+                });
+                i = end + 1;
+            }
+            Token::Open("hr", _) => {
+                blocks.push(Block::Rule);
+                i += 1;
+            }
+            Token::Text(s) if s.trim().is_empty() => i += 1,
+            _ => {
+                if blocks.last() != Some(&Block::Html) {
+                    blocks.push(Block::Html);
+                }
+                i += 1;
+            }
+        }
+    }
+    blocks
+}
+
+fn word_style(kind: WordType) -> Option<Style> {
+    match kind {
+        WordType::Bold => Some(Style::Bold),
+        WordType::BoldItalic => Some(Style::BoldItalic),
+        WordType::Italic => Some(Style::Italic),
+        WordType::Code => Some(Style::Code),
+        WordType::Strikethrough => Some(Style::Strikethrough),
+        WordType::Link => Some(Style::Link),
+        WordType::ListMarker
+        | WordType::MetaInfo(_)
+        | WordType::LinkData
+        | WordType::FootnoteData => None,
+        _ => Some(Style::Plain),
+    }
+}
+
+fn component_text(component: &TextComponent, skip: usize) -> String {
+    let mut text = StyledText::default();
+    for line in component.content() {
+        for word in line.iter().skip(skip) {
+            if let Some(style) = word_style(word.kind()) {
+                text.push(style, word.content());
+            }
+        }
+        text.push(Style::Plain, " ");
+    }
+    let text = text.finish();
+
+    // Images that can't be loaded are shown as a placeholder paragraph
+    text.strip_prefix("Image not found/fetched [")
+        .and_then(|alt| alt.strip_suffix(']'))
+        .map_or(text.clone(), |alt| format!("<img>{alt}</img>"))
+}
+
+/// Reduce the components `parse_markdown` produced to their blocks.
+fn actual_blocks(markdown: &str) -> Vec<Block> {
+    let root = parse_markdown_with(ParserBackend::Pest, None, markdown, 1000);
+    let mut blocks = Vec::new();
+    let mut in_quote = false;
+    for child in root.children() {
+        let component = match child {
+            Component::TextComponent(component) => component,
+            Component::Image(_) => {
+                blocks.push(Block::Paragraph("<img>image</img>".to_string()));
+                continue;
+            }
+        };
+
+        // Blocks nested in a list item or quote are part of that block
+        match component.containers().first() {
+            Some(Container::Quote(_)) => {
+                if !in_quote {
+                    blocks.push(Block::Quote);
+                    in_quote = true;
+                }
+                continue;
+            }
+            Some(Container::ListItem(_)) => {
+                if blocks.last() != Some(&Block::List) {
+                    blocks.push(Block::List);
+                }
+                continue;
+            }
+            Some(Container::Embed) => continue,
+            None => in_quote = false,
+        }
+
+        let block = match component.kind() {
+            TextNode::LineBreak => continue,
+            TextNode::Paragraph | TextNode::Image => Block::Paragraph(component_text(component, 0)),
+            TextNode::Heading => {
+                let level = component
+                    .meta_info()
+                    .iter()
+                    .find_map(|w| match w.kind() {
+                        WordType::MetaInfo(MetaData::HeadingLevel(level)) => Some(level),
+                        _ => None,
+                    })
+                    .unwrap_or(1);
+                // Headings below the first level start with their `#`s
+                let skip = usize::from(level > 1);
+                Block::Heading(level, component_text(component, skip))
+            }
+            TextNode::CodeBlock => {
+                let code: String = component
+                    .content()
+                    .iter()
+                    .flatten()
+                    .filter(|w| matches!(w.kind(), WordType::CodeBlock(_)))
+                    .map(|w| w.content())
+                    .collect();
+                let code = code.strip_prefix('\n').unwrap_or(&code);
+                Block::Code(code.to_string())
+            }
+            TextNode::List | TextNode::Task => Block::List,
+            TextNode::Quote | TextNode::CalloutTitle(_) => Block::Quote,
+            TextNode::Table(..) => Block::Table,
+            TextNode::HorizontalSeparator => Block::Rule,
+            TextNode::Footnote => Block::Footnote,
+            TextNode::DefinitionList(_) => Block::DefinitionList,
+            TextNode::DetailsSummary { .. } => Block::Html,
+            TextNode::EmbedTitle | TextNode::EmbedEnd => continue,
+        };
+        // Consecutive tasks are items of a single list
+        if !(block == Block::List
+            && component.kind() == TextNode::Task
+            && blocks.last() == Some(&Block::List))
+        {
+            blocks.push(block);
+        }
+    }
+    blocks
+}
+
+fn run_example(example: &Example) -> Result<(), String> {
+    let expected = expected_blocks(&example.html);
+    let actual = panic::catch_unwind(|| actual_blocks(&example.markdown))
+        .map_err(|_| "panicked while parsing".to_string())?;
+
+    if expected == actual {
+        return Ok(());
+    }
+
+    let list = |blocks: &[Block]| {
+        blocks
+            .iter()
+            .map(|b| format!("\n        {b}"))
+            .collect::<String>()
+    };
+    Err(format!(
+        "expected:{}\n    got:{}",
+        list(&expected),
+        list(&actual)
+    ))
+}
+
+fn load_expected_failures() -> BTreeSet<(String, usize)> {
+    let path = spec_dir().join(EXPECTED_FAILURES);
+    std::fs::read_to_string(path)
+        .unwrap_or_default()
+        .lines()
+        .filter(|line| !line.starts_with('#'))
+        .filter_map(|line| {
+            let (suite, number) = line.split_once(' ')?;
+            Some((suite.to_string(), number.trim().parse().ok()?))
+        })
+        .collect()
+}
+
+fn bless(examples: &[Example], failures: &BTreeSet<(String, usize)>) {
+    let mut out = String::from(
+        "# Spec examples the pest grammar is known to get wrong, one `<suite> <example>`\n\
+         # per line. Regenerate with `MDT_BLESS_SPEC=1 cargo test --test spec`.\n",
+    );
+    let mut section = "";
+    for example in examples {
+        if !failures.contains(&(example.suite.to_string(), example.number)) {
+            continue;
+        }
+        if example.section != section {
+            section = &example.section;
+            let _ = writeln!(out, "\n# {}: {section}", example.suite);
+        }
+        let _ = writeln!(out, "{} {}", example.suite, example.number);
+    }
+    std::fs::write(spec_dir().join(EXPECTED_FAILURES), out).unwrap();
+}
+
+#[test]
+fn spec_conformance() {
+    let examples: Vec<Example> = SUITES.into_iter().flat_map(load_examples).collect();
+    let expected_failures = load_expected_failures();
+
+    // Failing examples are reported below, the panic messages would only
+    // drown them out
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+    let results: Vec<_> = examples.iter().map(run_example).collect();
+    panic::set_hook(hook);
+
+    let failures: BTreeSet<(String, usize)> = examples
+        .iter()
+        .zip(&results)
+        .filter(|(_, result)| result.is_err())
+        .map(|(example, _)| (example.suite.to_string(), example.number))
+        .collect();
+
+    let mut report = String::from("Spec conformance\n\n");
+    let mut sections: Vec<(&str, &str, usize, usize)> = Vec::new();
+    for (example, result) in examples.iter().zip(&results) {
+        match sections.last_mut() {
+            Some((suite, section, passed, total))
+                if *suite == example.suite && *section == example.section =>
+            {
+                *passed += usize::from(result.is_ok());
+                *total += 1;
+            }
+            _ => sections.push((
+                example.suite,
+                &example.section,
+                usize::from(result.is_ok()),
+                1,
+            )),
+        }
+    }
+    for (suite, section, passed, total) in &sections {
+        let _ = writeln!(
+            report,
+            "{:<11} {section:<40} {passed:>4}/{total:<4} {:>5.1}%",
+            suite,
+            100.0 * *passed as f64 / *total as f64
+        );
+    }
+    let passed = results.iter().filter(|r| r.is_ok()).count();
+    let _ = writeln!(
+        report,
+        "\n{passed}/{} examples pass ({:.1}%)",
+        results.len(),
+        100.0 * passed as f64 / results.len() as f64
+    );
+
+    if std::env::var_os("MDT_BLESS_SPEC").is_some() {
+        bless(&examples, &failures);
+        println!("{report}");
+        return;
+    }
+
+    let mut unexpected = String::new();
+    for (example, result) in examples.iter().zip(&results) {
+        let key = (example.suite.to_string(), example.number);
+        match result {
+            Err(e) if !expected_failures.contains(&key) => {
+                let _ = writeln!(
+                    unexpected,
+                    "\n{} example {} ({}) regressed\n    markdown: {:?}\n    {e}",
+                    example.suite, example.number, example.section, example.markdown
+                );
+            }
+            Ok(()) if expected_failures.contains(&key) => {
+                let _ = writeln!(
+                    unexpected,
+                    "\n{} example {} ({}) passes now, remove it from {EXPECTED_FAILURES}",
+                    example.suite, example.number, example.section
+                );
+            }
+            _ => {}
+        }
+    }
+
+    println!("{report}");
+    assert!(unexpected.is_empty(), "{report}{unexpected}");
+}